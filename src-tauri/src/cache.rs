@@ -4,16 +4,23 @@
 //!   - Windows: `%APPDATA%/iter/projects/`
 //!   - Linux:   `$XDG_CONFIG_HOME/iter/projects/` (既定 `~/.config/iter/projects/`)
 //!   - macOS:   `~/Library/Application Support/iter/projects/`
-//! - キー: project root の絶対パスを SHA-like (DefaultHasher) でハッシュ
+//! - キー: project root の絶対パスを正規化 (小文字化、`\\` → `/`、末尾 `/` 除去)
+//!   して FNV-1a 64bit でハッシュ。`DefaultHasher` はアルゴリズムが Rust の
+//!   リリース間で保証されず、ツールチェインを上げるとキャッシュが全部外れるので使わない。
+//!   blake3 / sha256 は使わない: キーはファイル名にするだけで、読むときに記録済みの
+//!   root と照合するため衝突してもキャッシュミスになるだけ (別プロジェクトを返さない)。
+//!   `dirs` と同じく、そのためだけに依存クレートを増やさない
+//! - キー形式を変える前 (`DefaultHasher` の `{:x}`) のファイルは二度と引かれないので、
+//!   プロセスで最初に cache dir を開いたときに消す
 //! - 妥当性: cache 中の `root_mtime` と現在の root dir mtime を比較。一致なら fresh。
 //!
 //! `detect_project` 側からは `try_load` → ヒットなら即返す、無ければ通常走査して
 //! `save` する流れで使う。
 
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::sync::Once;
 use std::time::SystemTime;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,7 +35,31 @@ pub struct CachedProject<T: Serialize> {
 const CACHE_VERSION: u32 = 1;
 
 fn cache_dir() -> Option<PathBuf> {
-    dirs_like_config_dir().map(|d| d.join("iter").join("projects"))
+    let dir = dirs_like_config_dir()?.join("iter").join("projects");
+    static PURGE: Once = Once::new();
+    PURGE.call_once(|| purge_orphaned(&dir));
+    Some(dir)
+}
+
+/// 記録された root から今のキーで引けないキャッシュ (旧形式のキーで保存したものや
+/// 壊れたファイル) を消す。放っておくと読まれないまま溜まり続ける。
+fn purge_orphaned(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension() != Some(OsStr::new("json")) {
+            continue;
+        }
+        let root = std::fs::read(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice::<serde_json::Value>(&raw).ok())
+            .and_then(|v| Some(v.get("root")?.as_str()?.to_string()));
+        let stem = path.file_stem().and_then(OsStr::to_str);
+        if !root.is_some_and(|r| stem == Some(stable_key(Path::new(&r)).as_str())) {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
 }
 
 /// `dirs` クレートを入れずに OS ごとの config dir を返す軽量実装。
@@ -57,9 +88,27 @@ fn dirs_like_config_dir() -> Option<PathBuf> {
 
 fn cache_path(root: &Path) -> Option<PathBuf> {
     let dir = cache_dir()?;
-    let mut h = DefaultHasher::new();
-    root.to_string_lossy().to_lowercase().hash(&mut h);
-    Some(dir.join(format!("{:x}.json", h.finish())))
+    Some(dir.join(format!("{}.json", stable_key(root))))
+}
+
+/// 実行をまたいで同じ root なら同じになるキャッシュキー (16 桁 hex)。
+fn stable_key(root: &Path) -> String {
+    format!("{:016x}", fnv1a64(normalized_root(root).as_bytes()))
+}
+
+/// 小文字化、`\\` → `/`、末尾 `/` 除去。キーの元と、読み込み時の root 照合に使う
+fn normalized_root(root: &Path) -> String {
+    let s = root.to_string_lossy().replace('\\', "/").to_lowercase();
+    s.trim_end_matches('/').to_string()
+}
+
+/// FNV-1a (64bit)。暗号強度は不要で、実装が固定されていることだけが要件。
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes
+        .iter()
+        .fold(OFFSET, |h, &b| (h ^ u64::from(b)).wrapping_mul(PRIME))
 }
 
 fn root_mtime_secs(root: &Path) -> u64 {
@@ -91,6 +140,10 @@ where
     if version != CACHE_VERSION {
         return None;
     }
+    // キーが衝突した別プロジェクトのキャッシュは使わない
+    if normalized_root(Path::new(v.get("root")?.as_str()?)) != normalized_root(root) {
+        return None;
+    }
     let cached_root_mtime = v.get("root_mtime_secs")?.as_u64()?;
     if cached_root_mtime != root_mtime_secs(root) {
         return None; // root の更新時刻が変わっていればキャッシュは古い
@@ -123,3 +176,49 @@ pub fn invalidate(root: &Path) {
         let _ = std::fs::remove_file(p);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn fnv1a64_matches_reference_values() {
+        assert_eq!(fnv1a64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn stable_key_is_fixed_and_ignores_case_and_separators() {
+        // 値そのものを固定しておく (変わると既存キャッシュが全部外れる)
+        assert_eq!(stable_key(Path::new("c:/proj")), "b83453390d7b5626");
+        assert_eq!(stable_key(Path::new("C:\\Proj\\")), "b83453390d7b5626");
+        assert_eq!(stable_key(Path::new("C:/PROJ/")), "b83453390d7b5626");
+        assert_ne!(
+            stable_key(Path::new("c:/proj2")),
+            stable_key(Path::new("c:/proj"))
+        );
+    }
+
+    #[test]
+    fn purge_removes_entries_not_reachable_by_the_current_key() {
+        let d = tempdir().unwrap();
+        let dir = d.path();
+        let body = |root: &str| format!(r#"{{"root": {root:?}, "version": 1}}"#);
+        let current = dir.join(format!("{}.json", stable_key(Path::new("/p/a"))));
+        std::fs::write(&current, body("/p/a")).unwrap();
+        // DefaultHasher の {:x} で保存した旧形式のキー
+        let legacy = dir.join("3f2a9c0d1e.json");
+        std::fs::write(&legacy, body("/p/b")).unwrap();
+        let broken = dir.join("0000000000000000.json");
+        std::fs::write(&broken, "not json").unwrap();
+        let other = dir.join("notes.txt");
+        std::fs::write(&other, "keep").unwrap();
+
+        purge_orphaned(dir);
+        assert!(current.exists());
+        assert!(!legacy.exists());
+        assert!(!broken.exists());
+        assert!(other.exists());
+    }
+}
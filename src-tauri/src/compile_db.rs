@@ -3,6 +3,9 @@
 //! 検知ルール (優先順):
 //!   1. `<root>/compile_commands.json`
 //!   2. `<root>/build*/compile_commands.json` (浅く探索、`out/`, `cmake-build-debug/` 等)
+//!      - 複数見つかったら (host 用 + cross 用など) 名前順に 1 つへ統合し、
+//!        `<root>/.iter/merged/compile_commands.json` に書き出す。同じファイルの
+//!        entry が複数あれば名前順で先の build dir のものを使う
 //!   3. CMakeLists.txt 有り + `cmake` コマンド有り → `<root>/build/` に生成
 //!   4. CMakeLists.txt 無し → 仮想 CMakeLists を `<root>/.iter/CMakeLists.txt` に
 //!      生成して `<root>/.iter/build/compile_commands.json` まで作る:
@@ -18,6 +21,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 const VIRTUAL_DIR: &str = ".iter";
+const MERGED_DIR: &str = "merged";
 const SKIP_DIRS: &[&str] = &[
     ".git",
     ".iter",
@@ -36,7 +40,18 @@ const HEADER_EXTS: &[&str] = &["h", "hpp", "hxx", "hh"];
 
 /// compile_commands.json の場所を確定する。なければ生成する。
 pub fn ensure_compile_commands(root: &Path) -> Result<PathBuf, String> {
-    if let Some(p) = find_existing(root) {
+    let direct = root.join("compile_commands.json");
+    if direct.exists() {
+        return Ok(direct);
+    }
+    let mut found = find_build_dbs(root);
+    if found.len() > 1 {
+        let merged_dir = root.join(VIRTUAL_DIR).join(MERGED_DIR);
+        std::fs::create_dir_all(&merged_dir).map_err(|e| format!(".iter dir 作成失敗: {e}"))?;
+        write_iter_gitignore(&root.join(VIRTUAL_DIR));
+        return merge_compile_commands(&found, &merged_dir);
+    }
+    if let Some(p) = found.pop() {
         return Ok(p);
     }
     if root.join("CMakeLists.txt").exists() {
@@ -46,22 +61,89 @@ pub fn ensure_compile_commands(root: &Path) -> Result<PathBuf, String> {
     ensure_virtual_compile_commands(root)
 }
 
-fn find_existing(root: &Path) -> Option<PathBuf> {
-    let direct = root.join("compile_commands.json");
-    if direct.exists() {
-        return Some(direct);
+/// `<root>/*/compile_commands.json` を浅く探す (深さ 1)。read_dir の順序は FS
+/// 依存なので、毎回同じ優先順になるよう名前順に並べて返す
+fn find_build_dbs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        // 統合結果や仮想生成の出力を入力に混ぜない
+        .filter(|p| p.file_name().is_some_and(|n| n != VIRTUAL_DIR))
+        .collect();
+    dirs.sort();
+    dirs.into_iter()
+        .map(|d| d.join("compile_commands.json"))
+        .filter(|cand| cand.exists())
+        .collect()
+}
+
+/// 複数の compile_commands.json を `out_dir/compile_commands.json` に統合する。
+///
+/// 同じファイル (`directory` からの相対も解決して比較) の entry は `dbs` で先に
+/// 出たものだけ残す。読めない / JSON 配列でない DB は警告を出して飛ばす。
+/// 結果が 0 件 (全部読めなかった等) なら空の DB は書かず、先頭の DB をそのまま返す。
+fn merge_compile_commands(dbs: &[PathBuf], out_dir: &Path) -> Result<PathBuf, String> {
+    let mut seen = BTreeSet::new();
+    let mut merged = Vec::new();
+    for db in dbs {
+        let entries = match std::fs::read(db).map_err(|e| e.to_string()).and_then(|b| {
+            serde_json::from_slice::<Vec<serde_json::Value>>(&b).map_err(|e| e.to_string())
+        }) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("[compile-db] skip {}: {}", db.display(), e);
+                continue;
+            }
+        };
+        for entry in entries {
+            let Some(key) = entry_file_key(&entry) else {
+                continue;
+            };
+            if seen.insert(key) {
+                merged.push(entry);
+            }
+        }
     }
-    // build*/compile_commands.json を浅く探す (深さ 1)
-    for entry in std::fs::read_dir(root).ok()?.flatten() {
-        let p = entry.path();
-        if p.is_dir() {
-            let cand = p.join("compile_commands.json");
-            if cand.exists() {
-                return Some(cand);
+    if merged.is_empty() {
+        eprintln!(
+            "[compile-db] merge produced no entries; using {}",
+            dbs[0].display()
+        );
+        return Ok(dbs[0].clone());
+    }
+
+    let cc = out_dir.join("compile_commands.json");
+    let body = serde_json::to_vec_pretty(&merged)
+        .map_err(|e| format!("compile_commands.json 統合失敗: {e}"))?;
+    std::fs::write(&cc, body).map_err(|e| format!("compile_commands.json 書き込み失敗: {e}"))?;
+    Ok(cc)
+}
+
+/// entry の `file` を `directory` 基準で絶対化し、`.` / `..` を字面で畳んで
+/// 区切りを `/` に揃えたもの。
+fn entry_file_key(entry: &serde_json::Value) -> Option<String> {
+    use std::path::Component;
+
+    let file = Path::new(entry.get("file")?.as_str()?);
+    let path = match entry.get("directory").and_then(|d| d.as_str()) {
+        Some(dir) if file.is_relative() => Path::new(dir).join(file),
+        _ => file.to_path_buf(),
+    };
+    let mut normalized = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
             }
+            other => normalized.push(other),
         }
     }
-    None
+    Some(cmake_path(&normalized))
 }
 
 /// `<root>/.iter/CMakeLists.txt` を生成し、可能なら cmake で
@@ -418,6 +500,108 @@ mod tests {
         assert!(body.contains("main.cpp"));
     }
 
+    #[test]
+    fn find_build_dbs_lists_build_dirs_in_name_order() {
+        let d = tempdir().unwrap();
+        let root = d.path();
+        // 作成順と名前順をわざと逆にする
+        write(&root.join("out/compile_commands.json"), "[]");
+        write(&root.join("cmake-build-debug/compile_commands.json"), "[]");
+        write(&root.join("build/compile_commands.json"), "[]");
+        write(&root.join(".iter/compile_commands.json"), "[]"); // 入力にしない
+
+        assert_eq!(
+            find_build_dbs(root),
+            vec![
+                root.join("build/compile_commands.json"),
+                root.join("cmake-build-debug/compile_commands.json"),
+                root.join("out/compile_commands.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ensure_prefers_root_compile_commands() {
+        let d = tempdir().unwrap();
+        let root = d.path();
+        write(&root.join("build/compile_commands.json"), "[]");
+        write(&root.join("compile_commands.json"), "[]");
+        assert_eq!(
+            ensure_compile_commands(root).unwrap(),
+            root.join("compile_commands.json")
+        );
+    }
+
+    #[test]
+    fn ensure_uses_single_build_dir_as_is() {
+        let d = tempdir().unwrap();
+        let root = d.path();
+        write(&root.join("build/compile_commands.json"), "[]");
+        assert_eq!(
+            ensure_compile_commands(root).unwrap(),
+            root.join("build/compile_commands.json")
+        );
+        assert!(!root.join(".iter/merged").exists());
+    }
+
+    #[test]
+    fn ensure_merges_multiple_build_dirs() {
+        let d = tempdir().unwrap();
+        let root = d.path();
+        write(
+            &root.join("build-host/compile_commands.json"),
+            r#"[
+                {"directory": "/p/build-host", "command": "c++ -c /p/host.cpp", "file": "/p/host.cpp"},
+                {"directory": "/p/build-host", "command": "c++ -DHOST -c ../common.cpp", "file": "../common.cpp"}
+            ]"#,
+        );
+        write(
+            &root.join("build-arm/compile_commands.json"),
+            r#"[
+                {"directory": "/p/build-arm", "command": "arm-c++ -c /p/arm.cpp", "file": "/p/arm.cpp"}
+            ]"#,
+        );
+        // 名前順で最後。common.cpp は build-host 側が勝つ
+        write(
+            &root.join("build-x/compile_commands.json"),
+            r#"[
+                {"directory": "/p", "command": "c++ -DX -c common.cpp", "file": "common.cpp"}
+            ]"#,
+        );
+        write(&root.join("out/compile_commands.json"), "not json");
+
+        let cc = ensure_compile_commands(root).unwrap();
+        assert_eq!(cc, root.join(".iter/merged/compile_commands.json"));
+
+        let v: Vec<serde_json::Value> =
+            serde_json::from_str(&fs::read_to_string(&cc).unwrap()).unwrap();
+        let cmds: Vec<&str> = v
+            .iter()
+            .map(|e| e.get("command").unwrap().as_str().unwrap())
+            .collect();
+        assert_eq!(
+            cmds,
+            vec![
+                "arm-c++ -c /p/arm.cpp",
+                "c++ -c /p/host.cpp",
+                "c++ -DHOST -c ../common.cpp",
+            ]
+        );
+    }
+
+    #[test]
+    fn ensure_falls_back_to_first_db_when_merge_is_empty() {
+        let d = tempdir().unwrap();
+        let root = d.path();
+        write(&root.join("build-a/compile_commands.json"), "not json");
+        write(&root.join("build-b/compile_commands.json"), "{}");
+        assert_eq!(
+            ensure_compile_commands(root).unwrap(),
+            root.join("build-a/compile_commands.json")
+        );
+        assert!(!root.join(".iter/merged/compile_commands.json").exists());
+    }
+
     #[test]
     fn ensure_virtual_errors_when_no_sources() {
         let d = tempdir().unwrap();
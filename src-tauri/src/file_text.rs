@@ -0,0 +1,265 @@
+//! File ウインドウに載せる本文の読み込み。
+//!
+//! フロントの `readTextFile` は UTF-8 として読めないファイル (Latin-1 のコメント、
+//! バイナリ混じり) で丸ごと失敗し、巨大な生成コードはそのまま全部読んでしまう。
+//! ここでは
+//! - 不正なバイト列を U+FFFD に置き換えて読み (`lossy`)、
+//! - `max_bytes` を超えるファイルは対象行の前後 `PARTIAL_WINDOW_LINES` 行だけ読む
+//!   (`partial`)。窓の中身も `max_bytes` までで打ち切る (minified など改行の
+//!   ほとんど無いファイルは 1 行で上限を超えるため。`truncated`)。
+//!
+//! どちらの場合もフロントは header に表示し、保存を止める (書き戻すと
+//! 置換文字や欠けた行でファイルを壊すため)。
+//!
+//! `readTextFile` と同じく fs プラグインの scope に従い、scope 外 (`$HOME/.ssh`
+//! など deny 指定の場所を含む) は読まずに PermissionDenied を返す。判定は
+//! capability の `fs:scope` (global / command scope) と実行時に足した project
+//! root (`fs_scope()`) を合わせて行い、どちらかで deny されていれば allow より優先する。
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use tauri::ipc::{CommandScope, GlobalScope};
+use tauri::utils::config::FsScope;
+use tauri_plugin_fs::{Entry, FsExt};
+
+use crate::snippet::SnippetError;
+
+/// max_bytes 未指定時の上限
+const DEFAULT_MAX_BYTES: u64 = 16 * 1024 * 1024;
+/// 部分読み込み時に対象行の前後それぞれ何行読むか
+const PARTIAL_WINDOW_LINES: u32 = 2000;
+
+#[derive(Debug, Serialize)]
+pub struct FileText {
+    pub text: String,
+    /// UTF-8 として不正なバイトを置換した
+    pub lossy: bool,
+    /// 上限超えで一部だけ読んだ
+    pub partial: bool,
+    /// partial の窓も max_bytes に収まらず、途中の行で切った
+    pub truncated: bool,
+    /// text の 1 行目がファイルの何行目か (0-based)。partial でなければ 0
+    pub start_line: u32,
+}
+
+#[tauri::command]
+pub fn read_file_text(
+    app: tauri::AppHandle,
+    global_scope: GlobalScope<Entry>,
+    command_scope: CommandScope<Entry>,
+    path: String,
+    line: Option<u32>,
+    max_bytes: Option<u64>,
+) -> Result<FileText, SnippetError> {
+    if !is_path_readable(&app, &global_scope, &command_scope, &path) {
+        return Err(SnippetError::PermissionDenied(path));
+    }
+    read_file_text_windowed(
+        &path,
+        line.unwrap_or(0),
+        max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+        PARTIAL_WINDOW_LINES,
+    )
+}
+
+/// fs プラグインのコマンドと同じ基準で path を読んでよいか判定する。
+/// capability 側 (global + command scope) と実行時 scope のどちらかで deny なら不可、
+/// そうでなければどちらかで allow されていれば可。
+pub(crate) fn is_path_readable(
+    app: &tauri::AppHandle,
+    global_scope: &GlobalScope<Entry>,
+    command_scope: &CommandScope<Entry>,
+    path: &str,
+) -> bool {
+    let paths = |entries: &[std::sync::Arc<Entry>]| -> Vec<std::path::PathBuf> {
+        entries.iter().filter_map(|e| e.path.clone()).collect()
+    };
+    let declared = FsScope::Scope {
+        allow: [paths(global_scope.allows()), paths(command_scope.allows())].concat(),
+        deny: [paths(global_scope.denies()), paths(command_scope.denies())].concat(),
+        require_literal_leading_dot: None,
+    };
+    let runtime = app.fs_scope();
+    let Ok(declared) = tauri::scope::fs::Scope::new(app, &declared) else {
+        return false;
+    };
+    if declared.is_forbidden(path) || runtime.is_forbidden(path) {
+        return false;
+    }
+    declared.is_allowed(path) || runtime.is_allowed(path)
+}
+
+fn read_file_text_windowed(
+    path: &str,
+    line: u32,
+    max_bytes: u64,
+    window: u32,
+) -> Result<FileText, SnippetError> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| SnippetError::from_io(e, path))?
+        .len();
+    if size <= max_bytes {
+        let bytes = std::fs::read(path).map_err(|e| SnippetError::from_io(e, path))?;
+        let (text, lossy) = decode_lossy(bytes);
+        return Ok(FileText {
+            text,
+            lossy,
+            partial: false,
+            truncated: false,
+            start_line: 0,
+        });
+    }
+
+    // 先頭から行単位で流し読みし、窓に入った行だけ残す。窓を抜けるか、
+    // 残した量が max_bytes に達したら打ち切る
+    let start = line.saturating_sub(window);
+    let end = line.saturating_add(window).saturating_add(1);
+    let file = File::open(path).map_err(|e| SnippetError::from_io(e, path))?;
+    let mut reader = BufReader::new(file);
+    let mut kept = Vec::new();
+    let mut buf = Vec::new();
+    let mut truncated = false;
+    let mut n: u32 = 0;
+    while n < end {
+        buf.clear();
+        let read = if n >= start {
+            // 窓の中の行は残りの枠 + 1 byte までしか読まない (巨大な 1 行を抱え込まない)
+            let room = max_bytes.saturating_sub(kept.len() as u64);
+            (&mut reader).take(room + 1).read_until(b'\n', &mut buf)
+        } else {
+            reader.read_until(b'\n', &mut buf)
+        }
+        .map_err(|e| SnippetError::from_io(e, path))?;
+        if read == 0 {
+            break;
+        }
+        if n >= start {
+            let room = (max_bytes - kept.len() as u64) as usize;
+            if buf.len() > room {
+                kept.extend_from_slice(&buf[..floor_char_boundary(&buf, room)]);
+                truncated = true;
+                break;
+            }
+            kept.extend_from_slice(&buf);
+        }
+        n += 1;
+    }
+    // 最後の改行は次の (読んでいない) 行との区切りなので落とす
+    if kept.last() == Some(&b'\n') {
+        kept.pop();
+        if kept.last() == Some(&b'\r') {
+            kept.pop();
+        }
+    }
+    let (text, lossy) = decode_lossy(kept);
+    Ok(FileText {
+        text,
+        lossy,
+        partial: true,
+        truncated,
+        start_line: start.min(n),
+    })
+}
+
+/// `i` 以下で UTF-8 の文字境界になる位置。切った末尾を U+FFFD にしないため
+fn floor_char_boundary(bytes: &[u8], mut i: usize) -> usize {
+    while i > 0 && i < bytes.len() && bytes[i] & 0xC0 == 0x80 {
+        i -= 1;
+    }
+    i
+}
+
+/// 不正なバイト列を U+FFFD にして String にする。置換が起きたら true。
+fn decode_lossy(bytes: Vec<u8>) -> (String, bool) {
+    match String::from_utf8(bytes) {
+        Ok(s) => (s, false),
+        Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// コマンドの既定値 (scope 判定は AppHandle が要るので除く) で読む
+    fn read_whole(path: &str, line: u32) -> FileText {
+        read_file_text_windowed(path, line, DEFAULT_MAX_BYTES, PARTIAL_WINDOW_LINES).unwrap()
+    }
+
+    #[test]
+    fn latin1_comment_is_decoded_lossily() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("latin1.cpp");
+        fs::write(&path, b"// caf\xe9\nint x;\n").unwrap();
+        let ft = read_whole(&path.to_string_lossy(), 0);
+        assert!(ft.lossy);
+        assert!(!ft.partial);
+        assert_eq!(ft.text, "// caf\u{FFFD}\nint x;\n");
+    }
+
+    #[test]
+    fn utf8_file_is_read_whole() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("ok.cpp");
+        fs::write(&path, "// 日本語\nint x;\n").unwrap();
+        let ft = read_whole(&path.to_string_lossy(), 1);
+        assert!(!ft.lossy && !ft.partial);
+        assert_eq!(ft.start_line, 0);
+        assert_eq!(ft.text, "// 日本語\nint x;\n");
+    }
+
+    #[test]
+    fn oversized_file_loads_only_a_window_around_the_target() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("generated.cpp");
+        let body: String = (0..1000).map(|i| format!("line{i}\n")).collect();
+        fs::write(&path, &body).unwrap();
+        let p = path.to_string_lossy().into_owned();
+
+        let ft = read_file_text_windowed(&p, 500, 1024, 3).unwrap();
+        assert!(ft.partial);
+        assert_eq!(ft.start_line, 497);
+        assert_eq!(
+            ft.text,
+            "line497\nline498\nline499\nline500\nline501\nline502\nline503"
+        );
+
+        // 先頭付近は 0 行目から
+        let head = read_file_text_windowed(&p, 1, 1024, 3).unwrap();
+        assert_eq!(head.start_line, 0);
+        assert_eq!(head.text.lines().count(), 5);
+    }
+
+    #[test]
+    fn single_line_oversized_file_is_cut_at_max_bytes() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("bundle.min.js");
+        // 改行なしの 1 行。3 byte 文字なので 100 byte 目は文字の途中
+        let line = "x".repeat(11) + &"あ".repeat(1000);
+        fs::write(&path, &line).unwrap();
+
+        let ft = read_file_text_windowed(&path.to_string_lossy(), 0, 100, 3).unwrap();
+        assert!(ft.partial && ft.truncated);
+        assert!(!ft.lossy);
+        assert_eq!(ft.start_line, 0);
+        assert_eq!(ft.text, "x".repeat(11) + &"あ".repeat(29));
+
+        // 窓が上限に収まれば truncated にしない
+        let ok = read_file_text_windowed(&path.to_string_lossy(), 0, 100_000, 3).unwrap();
+        assert!(!ok.partial && !ok.truncated);
+    }
+
+    #[test]
+    fn target_past_eof_in_oversized_file_yields_empty_window() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("generated.cpp");
+        fs::write(&path, "a\nb\nc\n".repeat(100)).unwrap();
+        let ft = read_file_text_windowed(&path.to_string_lossy(), 10_000, 16, 3).unwrap();
+        assert!(ft.partial);
+        assert_eq!(ft.start_line, 300);
+        assert!(ft.text.is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! File ウインドウ向けの git 情報 (変更行 gutter と blame)。
+//!
+//! - `git_line_changes`: `git diff -U0 HEAD -- <file>` の hunk ヘッダ
+//!   (`@@ -a,b +c,d @@`) だけを読み、追加 / 変更 / 削除の行範囲に直す。
+//!   git が無い・リポジトリ外などは Err で返し、フロントは gutter を出さない
+//!   だけにする (エラー表示はしない)。未追跡のファイルは `git diff` が差分なしで
+//!   正常終了するので、Err ではなく空の変更列になる。
+//! - `git_blame`: 指定行範囲だけ `git blame --line-porcelain -L` する。
+//!   巨大ファイルでも表示中の範囲しか走らせない。
+//!
+//! どちらも `read_file_text` と同じ fs scope 判定を通し、git の起動は
+//! blocking スレッドで行う (IPC のスレッドを塞がない)。
+
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::Command;
+use tauri::ipc::{CommandScope, GlobalScope};
+use tauri_plugin_fs::Entry;
+
+use crate::file_text::is_path_readable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    /// 行が消えた位置。start_line はその直前の行 (先頭で消えたなら 0)
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineChange {
+    pub kind: ChangeKind,
+    /// 作業ツリー側の 0-based 開始行
+    pub start_line: u32,
+    /// 行数。Removed は常に 1 (印を付ける行の数)
+    pub line_count: u32,
+}
+
+#[tauri::command]
+pub async fn git_line_changes(
+    app: tauri::AppHandle,
+    global_scope: GlobalScope<Entry>,
+    command_scope: CommandScope<Entry>,
+    path: String,
+) -> Result<Vec<LineChange>, String> {
+    if !is_path_readable(&app, &global_scope, &command_scope, &path) {
+        return Err(format!("fs scope 外のファイルです: {path}"));
+    }
+    tauri::async_runtime::spawn_blocking(move || line_changes(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn line_changes(path: &str) -> Result<Vec<LineChange>, String> {
+    let file = Path::new(path);
+    let dir = file.parent().ok_or("親ディレクトリがありません")?;
+    let name = file.file_name().ok_or("ファイル名がありません")?;
+    let git = which::which("git").map_err(|_| "git が PATH に見つかりません".to_string())?;
+
+    let output = Command::new(git)
+        .arg("-C")
+        .arg(dir)
+        .args(["diff", "-U0", "--no-color", "--no-ext-diff", "HEAD", "--"])
+        .arg(name)
+        .output()
+        .map_err(|e| format!("git 起動失敗: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git diff 失敗: {}", stderr.trim()));
+    }
+    Ok(parse_hunks(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlameLine {
+    /// 作業ツリー側の 0-based 行
+    pub line: u32,
+    /// commit hash (SHA-1 は 40 桁、SHA-256 のリポジトリは 64 桁)。未コミットの行は 0 埋め
+    pub hash: String,
+    pub author: String,
+    /// author-time (unix 秒)
+    pub author_time: i64,
+}
+
+/// `[start_line, end_line)` (0-based) の blame。
+#[tauri::command]
+pub async fn git_blame(
+    app: tauri::AppHandle,
+    global_scope: GlobalScope<Entry>,
+    command_scope: CommandScope<Entry>,
+    path: String,
+    start_line: u32,
+    end_line: u32,
+) -> Result<Vec<BlameLine>, String> {
+    if !is_path_readable(&app, &global_scope, &command_scope, &path) {
+        return Err(format!("fs scope 外のファイルです: {path}"));
+    }
+    tauri::async_runtime::spawn_blocking(move || blame(&path, start_line, end_line))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn blame(path: &str, start_line: u32, end_line: u32) -> Result<Vec<BlameLine>, String> {
+    // エディタは末尾改行の後ろも 1 行と数えるが、git は数えない。
+    // -L がファイル末尾を越えると blame 全体が失敗するので、ここで詰める
+    let total = count_lines(Path::new(path)).map_err(|e| format!("read: {e}"))?;
+    let end_line = end_line.min(total);
+    if end_line <= start_line {
+        return Ok(Vec::new());
+    }
+    let file = Path::new(path);
+    let dir = file.parent().ok_or("親ディレクトリがありません")?;
+    let name = file.file_name().ok_or("ファイル名がありません")?;
+    let git = which::which("git").map_err(|_| "git が PATH に見つかりません".to_string())?;
+
+    let output = Command::new(git)
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--line-porcelain", "-L"])
+        .arg(format!("{},{}", start_line + 1, end_line))
+        .arg("--")
+        .arg(name)
+        .output()
+        .map_err(|e| format!("git 起動失敗: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git blame 失敗: {}", stderr.trim()));
+    }
+    Ok(parse_line_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// git が数える行数 (末尾の改行の後ろは数えない)。UTF-8 として読めないファイル
+/// でも数えられるよう、文字列にせずバイト列のまま流し読みで改行を数える。
+fn count_lines(path: &Path) -> std::io::Result<u32> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let mut count: u32 = 0;
+    let mut last = b'\n';
+    loop {
+        let buf = reader.fill_buf()?;
+        let Some(&tail) = buf.last() else {
+            break;
+        };
+        count += buf.iter().filter(|&&b| b == b'\n').count() as u32;
+        last = tail;
+        let n = buf.len();
+        reader.consume(n);
+    }
+    // 最終行に改行が無ければその行も 1 行
+    Ok(if last == b'\n' { count } else { count + 1 })
+}
+
+/// `--line-porcelain` 出力を行ごとの BlameLine にする。
+///
+/// 各行は `<hash> <orig> <final> [<count>]` で始まり、`author` などの
+/// ヘッダが続き、タブで始まる本文行で終わる。
+pub fn parse_line_porcelain(out: &str) -> Vec<BlameLine> {
+    let mut result = Vec::new();
+    let mut current: Option<BlameLine> = None;
+    for line in out.lines() {
+        if line.starts_with('\t') {
+            result.extend(current.take());
+            continue;
+        }
+        match current.as_mut() {
+            None => {
+                let mut parts = line.split(' ');
+                let hash = parts.next().unwrap_or_default();
+                let final_line: Option<u32> = parts.nth(1).and_then(|s| s.parse().ok());
+                let is_hash =
+                    matches!(hash.len(), 40 | 64) && hash.bytes().all(|b| b.is_ascii_hexdigit());
+                if let (true, Some(n)) = (is_hash, final_line) {
+                    current = Some(BlameLine {
+                        line: n.saturating_sub(1),
+                        hash: hash.to_string(),
+                        author: String::new(),
+                        author_time: 0,
+                    });
+                }
+            }
+            Some(b) => {
+                if let Some(v) = line.strip_prefix("author ") {
+                    b.author = v.to_string();
+                } else if let Some(v) = line.strip_prefix("author-time ") {
+                    b.author_time = v.parse().unwrap_or(0);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// `-U0` の unified diff から hunk ヘッダを拾って LineChange 列にする。
+pub fn parse_hunks(diff: &str) -> Vec<LineChange> {
+    diff.lines().filter_map(parse_hunk_header).collect()
+}
+
+fn parse_hunk_header(line: &str) -> Option<LineChange> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (old, rest) = rest.split_once(" +")?;
+    let (new, _) = rest.split_once(" @@")?;
+    let (_, old_count) = parse_range(old)?;
+    let (new_start, new_count) = parse_range(new)?;
+
+    // new_start は 1-based。count 0 のときは「この行の直後」を指す
+    let change = if new_count == 0 {
+        LineChange {
+            kind: ChangeKind::Removed,
+            start_line: new_start.saturating_sub(1),
+            line_count: 1,
+        }
+    } else {
+        LineChange {
+            kind: if old_count == 0 {
+                ChangeKind::Added
+            } else {
+                ChangeKind::Modified
+            },
+            start_line: new_start - 1,
+            line_count: new_count,
+        }
+    };
+    Some(change)
+}
+
+/// `12,3` → (12, 3)、`12` → (12, 1)
+fn parse_range(s: &str) -> Option<(u32, u32)> {
+    match s.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((s.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn change(kind: ChangeKind, start_line: u32, line_count: u32) -> LineChange {
+        LineChange {
+            kind,
+            start_line,
+            line_count,
+        }
+    }
+
+    const DIFF: &str = "\
+diff --git a/src/foo.cpp b/src/foo.cpp
+index 1111111..2222222 100644
+--- a/src/foo.cpp
++++ b/src/foo.cpp
+@@ -0,0 +1,2 @@
++#include <vector>
++#include <string>
+@@ -10 +12 @@ int main() {
+-  return 0;
++  return run();
+@@ -20,3 +21,0 @@ void helper() {
+-  a();
+-  b();
+-  c();
+@@ -40,2 +38,4 @@
+-x
+-y
++x1
++y1
++z1
++w1
+";
+
+    #[test]
+    fn classifies_added_modified_removed_hunks() {
+        let changes = parse_hunks(DIFF);
+        assert_eq!(
+            changes,
+            vec![
+                change(ChangeKind::Added, 0, 2),
+                change(ChangeKind::Modified, 11, 1),
+                change(ChangeKind::Removed, 20, 1),
+                change(ChangeKind::Modified, 37, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn removal_at_top_of_file_marks_line_zero() {
+        let changes = parse_hunks("@@ -1,2 +0,0 @@\n-a\n-b\n");
+        assert_eq!(changes, vec![change(ChangeKind::Removed, 0, 1)]);
+    }
+
+    #[test]
+    fn parses_line_porcelain_per_line() {
+        let out = "\
+1234567890123456789012345678901234567890 3 5 2
+author Alice Example
+author-mail <alice@example.com>
+author-time 1700000000
+author-tz +0900
+summary Add foo
+filename src/foo.cpp
+\tint foo();
+1234567890123456789012345678901234567890 4 6
+author Alice Example
+author-time 1700000000
+filename src/foo.cpp
+\tint bar();
+0000000000000000000000000000000000000000 7 7 1
+author Not Committed Yet
+author-time 1800000000
+filename src/foo.cpp
+\t// author wip
+";
+        let lines = parse_line_porcelain(out);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].line, 4);
+        assert_eq!(lines[0].author, "Alice Example");
+        assert_eq!(lines[0].author_time, 1_700_000_000);
+        assert_eq!(lines[1].line, 5);
+        assert_eq!(lines[2].line, 6);
+        assert_eq!(lines[2].hash, "0".repeat(40));
+        // 本文行の "author" はヘッダとして読まない
+        assert_eq!(lines[2].author, "Not Committed Yet");
+    }
+
+    #[test]
+    fn parses_sha256_line_porcelain() {
+        let hash = "ab".repeat(32);
+        let out = format!("{hash} 1 1 1\nauthor Bob\nauthor-time 1700000000\n\tint x;\n");
+        let lines = parse_line_porcelain(&out);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].hash, hash);
+        assert_eq!(lines[0].author, "Bob");
+        // 40 / 64 桁以外や 16 進でない先頭語は hash とみなさない
+        assert!(parse_line_porcelain(&format!("{} 1 1 1\n\tx\n", "a".repeat(50))).is_empty());
+        assert!(parse_line_porcelain(&format!("{} 1 1 1\n\tx\n", "z".repeat(40))).is_empty());
+    }
+
+    #[test]
+    fn counts_lines_like_git_even_for_non_utf8_files() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("a.cpp");
+        std::fs::write(&path, b"// caf\xe9\nint x;\n").unwrap();
+        assert_eq!(count_lines(&path).unwrap(), 2);
+        std::fs::write(&path, b"a\nb").unwrap();
+        assert_eq!(count_lines(&path).unwrap(), 2);
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(count_lines(&path).unwrap(), 0);
+    }
+
+    #[test]
+    fn ignores_non_hunk_lines_and_empty_diff() {
+        assert!(parse_hunks("").is_empty());
+        assert!(parse_hunks("+@@ -1 +1 @@ looks like a header\n").is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 mod cache;
 mod compile_db;
+mod file_text;
+mod git;
 mod lsp;
 mod lsp_commands;
 mod project;
@@ -31,6 +33,9 @@ pub fn run() {
             lsp_commands::lsp_references,
             stack_trace::parse_stack_trace,
             snippet::read_snippet,
+            file_text::read_file_text,
+            git::git_line_changes,
+            git::git_blame,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
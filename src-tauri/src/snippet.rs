@@ -5,29 +5,162 @@
 //! Monaco を起動せず、軽量に多数のカードを描画するための API。
 
 use serde::Serialize;
+use std::io::ErrorKind;
+use thiserror::Error;
+
+/// これより大きいファイルはスニペットを取らない (生成コードの巨大ファイルを
+/// 丸ごと読んで IPC に流さないため)。
+const MAX_FILE_BYTES: u64 = 32 * 1024 * 1024;
 
 #[derive(Debug, Serialize)]
 pub struct Snippet {
     /// 0-based の最初の行番号
     pub start_line: u32,
-    /// クエリされた行 (0-based)
+    /// クエリされた行 (0-based)。ファイル末尾を越えていてもそのまま返す
     pub target_line: u32,
+    /// ファイル全体の行数。target_line >= total_lines なら対象行はもう存在しない
+    pub total_lines: u32,
     pub lines: Vec<String>,
 }
 
+/// スニペットを取れなかった理由。カードに本文の代わりに表示する。
+#[derive(Debug, Error, Serialize)]
+pub enum SnippetError {
+    #[error("file not found: {0}")]
+    NotFound(String),
+    #[error("permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("file too large: {path} ({size} bytes)")]
+    TooLarge { path: String, size: u64 },
+    #[error("io: {0}")]
+    Io(String),
+}
+
+impl SnippetError {
+    pub(crate) fn from_io(e: std::io::Error, path: &str) -> Self {
+        match e.kind() {
+            ErrorKind::NotFound => SnippetError::NotFound(path.to_string()),
+            ErrorKind::PermissionDenied => SnippetError::PermissionDenied(path.to_string()),
+            _ => SnippetError::Io(e.to_string()),
+        }
+    }
+}
+
 #[tauri::command]
-pub fn read_snippet(path: String, line: u32, context: u32) -> Result<Snippet, String> {
-    let text = std::fs::read_to_string(&path).map_err(|e| format!("read: {e}"))?;
+pub fn read_snippet(path: String, line: u32, context: u32) -> Result<Snippet, SnippetError> {
+    read_snippet_capped(&path, line, context, MAX_FILE_BYTES)
+}
+
+fn read_snippet_capped(
+    path: &str,
+    line: u32,
+    context: u32,
+    max_bytes: u64,
+) -> Result<Snippet, SnippetError> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| SnippetError::from_io(e, path))?
+        .len();
+    if size > max_bytes {
+        return Err(SnippetError::TooLarge {
+            path: path.to_string(),
+            size,
+        });
+    }
+    // Latin-1 のコメント等で読めなくならないよう、不正なバイトは U+FFFD にする
+    let bytes = std::fs::read(path).map_err(|e| SnippetError::from_io(e, path))?;
+    let text = String::from_utf8_lossy(&bytes);
     let all: Vec<&str> = text.split('\n').collect();
     let total = all.len();
     let target = line as usize;
     let ctx = context as usize;
-    let start = target.saturating_sub(ctx);
-    let end = (target + ctx + 1).min(total);
+    // 編集で行が減ると古い位置情報が EOF の先を指す。末尾行を軸に窓を取り直す
+    // (そのままだと start > end で slice が panic する)
+    let anchor = target.min(total - 1);
+    let start = anchor.saturating_sub(ctx);
+    let end = (anchor + ctx + 1).min(total);
     let lines = all[start..end].iter().map(|s| s.to_string()).collect();
     Ok(Snippet {
         start_line: start as u32,
         target_line: target as u32,
+        total_lines: total as u32,
         lines,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn read_snippet_keeps_long_multibyte_lines_whole() {
+        // byte 80 が文字の途中に当たる行。スニペットは行を切らずにそのまま返す
+        let d = tempdir().unwrap();
+        let path = d.path().join("long.cpp");
+        let long = "// ".to_string() + &"日本語のコメント".repeat(10);
+        assert!(!long.is_char_boundary(80));
+        fs::write(&path, format!("int a;\n{long}\nint b;\n")).unwrap();
+
+        let snip = read_snippet(path.to_string_lossy().into_owned(), 1, 1).unwrap();
+        assert_eq!(snip.start_line, 0);
+        assert_eq!(snip.lines, vec!["int a;", long.as_str(), "int b;"]);
+    }
+
+    fn write_lines(n: usize) -> (tempfile::TempDir, String) {
+        let d = tempdir().unwrap();
+        let path = d.path().join("f.cpp");
+        let body: Vec<String> = (0..n).map(|i| format!("l{i}")).collect();
+        fs::write(&path, body.join("\n")).unwrap();
+        (d, path.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn read_snippet_reports_missing_file() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("moved-away.cpp");
+        let err = read_snippet(path.to_string_lossy().into_owned(), 0, 3).unwrap_err();
+        assert!(matches!(err, SnippetError::NotFound(_)), "{err:?}");
+    }
+
+    #[test]
+    fn read_snippet_replaces_invalid_utf8() {
+        let d = tempdir().unwrap();
+        let path = d.path().join("latin1.cpp");
+        fs::write(&path, b"// caf\xe9\nint x;\n").unwrap();
+        let snip = read_snippet(path.to_string_lossy().into_owned(), 0, 3).unwrap();
+        assert_eq!(snip.lines[0], "// caf\u{FFFD}");
+        assert_eq!(snip.lines[1], "int x;");
+    }
+
+    #[test]
+    fn read_snippet_refuses_oversized_files() {
+        let (_d, path) = write_lines(100);
+        let err = read_snippet_capped(&path, 0, 3, 64).unwrap_err();
+        assert!(matches!(err, SnippetError::TooLarge { .. }), "{err:?}");
+        assert!(read_snippet_capped(&path, 0, 3, 1024).is_ok());
+    }
+
+    #[test]
+    fn read_snippet_clamps_window_at_file_start_and_end() {
+        let (_d, path) = write_lines(10);
+        let first = read_snippet(path.clone(), 0, 3).unwrap();
+        assert_eq!(first.start_line, 0);
+        assert_eq!(first.lines, ["l0", "l1", "l2", "l3"]);
+
+        let last = read_snippet(path, 9, 3).unwrap();
+        assert_eq!(last.start_line, 6);
+        assert_eq!(last.lines, ["l6", "l7", "l8", "l9"]);
+        assert_eq!(last.total_lines, 10);
+    }
+
+    #[test]
+    fn read_snippet_past_eof_returns_tail_instead_of_panicking() {
+        let (_d, path) = write_lines(5);
+        let snip = read_snippet(path, 42, 2).unwrap();
+        assert_eq!(snip.target_line, 42);
+        assert_eq!(snip.total_lines, 5);
+        assert_eq!(snip.start_line, 2);
+        assert_eq!(snip.lines, ["l2", "l3", "l4"]);
+    }
+}
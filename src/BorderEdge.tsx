@@ -0,0 +1,49 @@
+/**
+ * カード中心どうしを結ぶ直線エッジ (放射配置用)。
+ *
+ * 放射配置では辺の向きが一定でないので handle をカード中央に置くが、
+ * React Flow の straight edge をそのまま使うと線と矢じりが相手カードの中心まで
+ * 伸び、ノードの下に描かれるエッジは矢じりごと隠れてしまう。ここでは両端を
+ * それぞれのカードの枠で切って描く。
+ */
+import { memo } from "react";
+import { BaseEdge, getStraightPath, useInternalNode, type EdgeProps } from "@xyflow/react";
+import { clipToRect } from "./layout";
+
+export const BorderEdge = memo(function BorderEdge(props: EdgeProps) {
+  const source = useInternalNode(props.source);
+  const target = useInternalNode(props.target);
+  const s = { x: props.sourceX, y: props.sourceY };
+  const t = { x: props.targetX, y: props.targetY };
+  const start = clipToRect(
+    t,
+    s,
+    (source?.measured.width ?? 0) / 2,
+    (source?.measured.height ?? 0) / 2,
+  );
+  const end = clipToRect(
+    s,
+    t,
+    (target?.measured.width ?? 0) / 2,
+    (target?.measured.height ?? 0) / 2,
+  );
+  const [path, labelX, labelY] = getStraightPath({
+    sourceX: start.x,
+    sourceY: start.y,
+    targetX: end.x,
+    targetY: end.y,
+  });
+  return (
+    <BaseEdge
+      id={props.id}
+      path={path}
+      markerEnd={props.markerEnd}
+      style={props.style}
+      label={props.label}
+      labelX={labelX}
+      labelY={labelY}
+      labelStyle={props.labelStyle}
+      labelBgStyle={props.labelBgStyle}
+    />
+  );
+});
@@ -1,9 +1,21 @@
-import { useCallback, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
-import { emit } from "@tauri-apps/api/event";
-import { lsp, win, type StackFrame } from "./lsp";
+import { emit, type UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import {
+  describeSnippetError,
+  fs,
+  loadMaxFileMb,
+  lsp,
+  MAX_FILE_MB_KEY,
+  win,
+  type StackFrame,
+} from "./lsp";
 import { StackTraceGraph } from "./StackTraceGraph";
+import { loadMaxCards, loadTabSize, MAX_CARDS_KEY, TAB_SIZE_KEY } from "./RelationCard";
+import { EDITOR_THEME_KEY, EDITOR_THEMES, loadEditorTheme } from "./editorTheme";
+import { useFullscreenShortcut } from "./fullscreen";
 
 interface ProjectInfo {
   root: string;
@@ -13,6 +25,16 @@ interface ProjectInfo {
 }
 
 const PROJECT_ROOT_KEY = "iter:project_root";
+/** グラフ化した直後に最初の in-project frame を開くか */
+const OPEN_FIRST_FRAME_KEY = "iter:open_first_frame";
+
+function loadOpenFirstFrame(): boolean {
+  try {
+    return localStorage.getItem(OPEN_FIRST_FRAME_KEY) === "1";
+  } catch {
+    return false;
+  }
+}
 
 interface FileNode {
   path: string;
@@ -29,6 +51,7 @@ const RELATION_KINDS = [
 ] as const;
 
 export function ControlPanel() {
+  useFullscreenShortcut();
   const [project, setProject] = useState<ProjectInfo | null>(null);
   const [loading, setLoading] = useState(false);
   const [lspState, setLspState] = useState<"idle" | "starting" | "ready" | "failed">(
@@ -42,6 +65,26 @@ export function ControlPanel() {
   // スタックトレース入力 + frame
   const [stackInput, setStackInput] = useState("");
   const [frames, setFrames] = useState<StackFrame[]>([]);
+  /** ファイルから読み込んだときの結果表示 */
+  const [stackNotice, setStackNotice] = useState<string | null>(null);
+  const [openFirstFrame, setOpenFirstFrame] = useState(loadOpenFirstFrame);
+
+  // 複数起動しても区別できるよう、タイトルにプロジェクト名と clangd / frame 数を出す
+  useEffect(() => {
+    if (!project) {
+      document.title = "Iter — Control Panel";
+      return;
+    }
+    const name = project.root.split(/[\\/]/).filter(Boolean).pop() ?? project.root;
+    const state =
+      lspState === "starting"
+        ? " (clangd 起動中…)"
+        : lspState === "failed"
+          ? " (clangd 失敗)"
+          : "";
+    const trace = frames.length > 0 ? ` — ${frames.length} frames` : "";
+    document.title = `Iter — ${name}${state}${trace}`;
+  }, [project, lspState, frames.length]);
 
   const pickProject = useCallback(async () => {
     const picked = await open({ directory: true, multiple: false });
@@ -98,11 +141,102 @@ export function ControlPanel() {
     });
   };
 
+  const toggleOpenFirstFrame = () => {
+    setOpenFirstFrame((prev) => {
+      try {
+        localStorage.setItem(OPEN_FIRST_FRAME_KEY, prev ? "0" : "1");
+      } catch {
+        // 保存できなくてもこのセッション中は効く
+      }
+      return !prev;
+    });
+  };
+
+  // 解析結果を反映し、設定が有効なら最初の in-project frame をカードのクリックと
+  // 同じ経路 (`open_at`) で開く。in-project な frame が無ければ何もしない
+  const showFrames = useCallback(
+    async (f: StackFrame[]) => {
+      setFrames(f);
+      if (!openFirstFrame) return;
+      const first = f.find((fr) => fr.in_project);
+      if (!first) return;
+      try {
+        // frame の line / column は 1-based
+        await win.openAt(
+          first.path,
+          Math.max(0, first.line - 1),
+          first.column ? first.column - 1 : 0,
+          false,
+        );
+      } catch (e) {
+        setError(`ファイルを開けませんでした: ${String(e)}`);
+      }
+    },
+    [openFirstFrame],
+  );
+
   const onParseStack = async () => {
     const f = await lsp.parseStackTrace(stackInput, project?.root);
-    setFrames(f);
+    await showFrames(f);
   };
 
+  // 保存済みのログ / トレースを読み込んで解析する。複数ファイルは順に連結
+  const loadStackFiles = useCallback(
+    async (paths: string[]) => {
+      try {
+        const texts: string[] = [];
+        let lossy = 0;
+        for (const p of paths) {
+          // ログは末尾にトレースがあることが多いので、File ウインドウ用の上限
+          // (超えると先頭付近だけ読む) は掛けずに全体を読む
+          const ft = await fs.readFileText(p, undefined, Number.MAX_SAFE_INTEGER);
+          texts.push(ft.text);
+          if (ft.lossy) lossy++;
+        }
+        const text = texts.join("\n");
+        setStackInput(text);
+        const f = await lsp.parseStackTrace(text, project?.root);
+        await showFrames(f);
+        setStackNotice(
+          `${paths.length} ファイルから ${f.length} frames を読み込みました` +
+            (lossy > 0 ? ` (${lossy} ファイルは UTF-8 でない文字を置換)` : ""),
+        );
+      } catch (e) {
+        setStackNotice(null);
+        setError(`スタックトレースの読み込みに失敗: ${describeSnippetError(e)}`);
+      }
+    },
+    [project?.root, showFrames],
+  );
+
+  const pickStackFiles = useCallback(async () => {
+    const picked = await open({ multiple: true, directory: false });
+    if (!picked) return;
+    const paths = Array.isArray(picked) ? picked : [picked];
+    if (paths.length > 0) await loadStackFiles(paths);
+  }, [loadStackFiles]);
+
+  // ウインドウへのファイルのドロップ → スタックトレースとして読み込む
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let cancelled = false;
+    (async () => {
+      const un = await getCurrentWebview().onDragDropEvent((e) => {
+        if (e.payload.type === "drop" && e.payload.paths.length > 0) {
+          void loadStackFiles(e.payload.paths);
+        }
+      });
+      // 登録が終わる前に cleanup 済み (StrictMode の再マウントや project 切替) なら
+      // ここで外さないと listener が残り、ドロップが二重に処理される
+      if (cancelled) un();
+      else unlisten = un;
+    })();
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [loadStackFiles]);
+
   const refreshProject = useCallback(async () => {
     if (!project) return;
     setLoading(true);
@@ -194,7 +328,7 @@ export function ControlPanel() {
           <textarea
             value={stackInput}
             onChange={(e) => setStackInput(e.target.value)}
-            placeholder="スタックトレースを貼り付け (gdb / lldb / sanitizer / V8 / Python / Rust 対応)"
+            placeholder="スタックトレースを貼り付け、またはログファイルをドロップ (gdb / lldb / sanitizer / V8 / Python / Rust 対応)"
             style={{
               width: "100%",
               minHeight: "100px",
@@ -212,6 +346,16 @@ export function ControlPanel() {
           <button onClick={onParseStack} disabled={!stackInput.trim()}>
             グラフ化 ({frames.length} frames)
           </button>
+          <button onClick={pickStackFiles} title="保存済みのログを読み込む (複数選択すると連結)">
+            ファイルから…
+          </button>
+          <div className="cp-checks">
+            <label>
+              <input type="checkbox" checked={openFirstFrame} onChange={toggleOpenFirstFrame} />
+              グラフ化したら最初の in-project frame を開く
+            </label>
+          </div>
+          {stackNotice && <div className="cp-stack-notice">{stackNotice}</div>}
           <div style={{ height: 300, marginTop: "0.5rem", border: "1px solid #1c1f27", borderRadius: 4 }}>
             <StackTraceGraph frames={frames} />
           </div>
@@ -220,6 +364,47 @@ export function ControlPanel() {
           </div>
         </section>
 
+        <section className="cp-section">
+          <h2>設定</h2>
+          <div className="cp-checks">
+            <NumberSetting
+              label="タブ幅"
+              storageKey={TAB_SIZE_KEY}
+              initial={loadTabSize()}
+              min={1}
+              max={16}
+            />
+            <label>
+              <select
+                defaultValue={loadEditorTheme()}
+                onChange={(e) => saveSetting(EDITOR_THEME_KEY, e.target.value)}
+              >
+                {EDITOR_THEMES.map((t) => (
+                  <option key={t} value={t}>
+                    {t}
+                  </option>
+                ))}
+              </select>
+              エディタのテーマ
+            </label>
+            <NumberSetting
+              label="読み込み上限 (MB、超えると対象行の周辺だけ開く)"
+              storageKey={MAX_FILE_MB_KEY}
+              initial={loadMaxFileMb()}
+              min={1}
+            />
+            <NumberSetting
+              label="1 グラフのカード上限"
+              storageKey={MAX_CARDS_KEY}
+              initial={loadMaxCards()}
+              min={1}
+            />
+          </div>
+          <div style={{ fontSize: "0.7rem", color: "#6b7383", marginTop: "0.3rem" }}>
+            次に開いた File Window / グラフから反映
+          </div>
+        </section>
+
         <section className="cp-section">
           <h2>ファイルツリー</h2>
         </section>
@@ -238,6 +423,53 @@ export function ControlPanel() {
   );
 }
 
+function saveSetting(key: string, value: string) {
+  try {
+    localStorage.setItem(key, value);
+  } catch {
+    // 保存できなければ読む側は既定値のまま
+  }
+}
+
+/**
+ * localStorage に保存する整数設定の 1 行。範囲外や入力途中の値は保存せず、
+ * 保存済みの値 (無ければ読む側 `loadX()` の既定値) のまま動く。
+ */
+function NumberSetting({
+  label,
+  storageKey,
+  initial,
+  min,
+  max,
+}: {
+  label: string;
+  storageKey: string;
+  initial: number;
+  min: number;
+  max?: number;
+}) {
+  const [text, setText] = useState(() => String(initial));
+  const onChange = (raw: string) => {
+    setText(raw);
+    const n = Number(raw);
+    if (raw === "" || !Number.isInteger(n) || n < min || (max !== undefined && n > max)) return;
+    saveSetting(storageKey, String(n));
+  };
+  return (
+    <label>
+      <input
+        type="number"
+        className="cp-number"
+        min={min}
+        max={max}
+        value={text}
+        onChange={(e) => onChange(e.target.value)}
+      />
+      {label}
+    </label>
+  );
+}
+
 function lspBadgeColor(state: string): string {
   switch (state) {
     case "ready":
@@ -1,15 +1,46 @@
-import { useCallback, useEffect, useMemo, useRef, useState } from "react";
+import {
+  useCallback,
+  useEffect,
+  useMemo,
+  useRef,
+  useState,
+  type KeyboardEvent as ReactKeyboardEvent,
+} from "react";
 import Editor, { type OnMount } from "@monaco-editor/react";
-import { readTextFile, writeTextFile } from "@tauri-apps/plugin-fs";
+import { stat, writeTextFile } from "@tauri-apps/plugin-fs";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import type * as Monaco from "monaco-editor";
-import { lsp, win, type CallHierarchyResult, type LspLocation } from "./lsp";
-import { RelationGraph, type RelationData } from "./RelationGraph";
+import {
+  describeSnippetError,
+  fs,
+  git,
+  loadMaxFileMb,
+  lsp,
+  win,
+  type BlameLine,
+  type CallHierarchyResult,
+  type FileText,
+  type LineChange,
+  type LspLocation,
+} from "./lsp";
+import {
+  RelationGraph,
+  type EdgeRouting,
+  type RelationData,
+  type RelationLayout,
+} from "./RelationGraph";
+import { loadTabSize } from "./RelationCard";
+import { defineIterTheme, loadEditorTheme } from "./editorTheme";
+import { blameLabel } from "./blame";
+import { useFullscreenShortcut } from "./fullscreen";
+import { domMeasure, ellipsizePath } from "./ellipsize";
 
 interface Props {
   path: string;
+  /** 0-based。`open_at` のクエリ `line` */
   initialLine?: number;
+  /** 0-based。`open_at` のクエリ `col` */
   initialCol?: number;
   followDefinition?: boolean;
 }
@@ -45,12 +76,91 @@ function languageOf(path: string): string {
 }
 
 const PROJECT_ROOT_KEY = "iter:project_root";
+const GRAPH_LAYOUT_KEY = "iter:graph_layout";
+/** radial 追加前の保存先。horizontal / vertical のどちらかが入っている */
+const LEGACY_GRAPH_DIRECTION_KEY = "iter:graph_direction";
+/** 切替ボタンで巡回する順 */
+const GRAPH_LAYOUTS: RelationLayout[] = ["horizontal", "vertical", "radial"];
+const GRAPH_LAYOUT_ICON: Record<RelationLayout, string> = {
+  horizontal: "⇆",
+  vertical: "⇅",
+  radial: "✲",
+};
+
+const EDGE_ROUTING_KEY = "iter:edge_routing";
+
+const WORD_WRAP_KEY = "iter:word_wrap";
+
+// overview ruler (スクロールバー上のマーク) の色。本文の装飾と色味を揃える
+const SEARCH_HIT_RULER_COLOR = "rgba(74, 122, 254, 0.8)";
+const SEARCH_CURRENT_RULER_COLOR = "#efc56a";
+const TARGET_RULER_COLOR = "rgba(239, 197, 106, 0.6)";
+/** blame は表示範囲の前後この行数まで先読みする */
+const BLAME_MARGIN = 50;
+const GIT_RULER_COLOR: Record<LineChange["kind"], string> = {
+  added: "#3fb950",
+  modified: "#4a7afe",
+  removed: "#e66060",
+};
+
+const DISK_POLL_MS = 1000;
+
+function loadMaxFileBytes(): number {
+  return loadMaxFileMb() * 1024 * 1024;
+}
+
+async function readMtime(path: string): Promise<number | null> {
+  try {
+    return (await stat(path)).mtime?.getTime() ?? null;
+  } catch {
+    return null;
+  }
+}
+
+function loadGraphLayout(): RelationLayout {
+  try {
+    const saved = (localStorage.getItem(GRAPH_LAYOUT_KEY) ??
+      localStorage.getItem(LEGACY_GRAPH_DIRECTION_KEY)) as RelationLayout | null;
+    if (!saved || !GRAPH_LAYOUTS.includes(saved)) return "horizontal";
+    // 旧キーから読んだ値は新キーへ移して以後は参照しない
+    localStorage.setItem(GRAPH_LAYOUT_KEY, saved);
+    localStorage.removeItem(LEGACY_GRAPH_DIRECTION_KEY);
+    return saved;
+  } catch {
+    return "horizontal";
+  }
+}
+
+function loadEdgeRouting(): EdgeRouting {
+  try {
+    return localStorage.getItem(EDGE_ROUTING_KEY) === "orthogonal" ? "orthogonal" : "curved";
+  } catch {
+    return "curved";
+  }
+}
 
 export function FileWindow({ path, initialLine, initialCol, followDefinition }: Props) {
   const [contents, setContents] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
+  /** 最後に読み込んだ / 保存した内容。contents と違えば未保存 (dirty) */
+  const [savedContents, setSavedContents] = useState<string | null>(null);
+  const [saveError, setSaveError] = useState<string | null>(null);
+  const [fileInfo, setFileInfo] = useState<{
+    lossy: boolean;
+    partial: boolean;
+    truncated: boolean;
+    startLine: number;
+  } | null>(null);
+  /** 読み込み / 保存後に別のエディタ等でディスク上のファイルが書き換えられた */
+  const [diskChanged, setDiskChanged] = useState(false);
+  const [blameOn, setBlameOn] = useState(false);
+  /** blame を出せなかった理由 (git 無し / 未追跡など)。1 行で header に出す */
+  const [blameNotice, setBlameNotice] = useState<string | null>(null);
+  /** 飛び先の行がファイル末尾を越えていたときの通知 */
+  const [targetNotice, setTargetNotice] = useState<string | null>(null);
   const [searchTerm, setSearchTerm] = useState("");
   const [matchCount, setMatchCount] = useState<number | null>(null);
+  const [matchIndex, setMatchIndex] = useState(0);
   const [showRelations, setShowRelations] = useState({
     callers: true,
     callees: true,
@@ -58,39 +168,186 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
   });
   const [relationData, setRelationData] = useState<RelationData | null>(null);
   const [graphCollapsed, setGraphCollapsed] = useState(false);
+  const [graphLayout, setGraphLayout] = useState<RelationLayout>(loadGraphLayout);
+  const [edgeRouting, setEdgeRouting] = useState<EdgeRouting>(loadEdgeRouting);
+  const tabSize = useMemo(loadTabSize, []);
+  const editorTheme = useMemo(loadEditorTheme, []);
+  const [wordWrap, setWordWrap] = useState(() => {
+    try {
+      return localStorage.getItem(WORD_WRAP_KEY) === "on";
+    } catch {
+      return false;
+    }
+  });
 
   const editorRef = useRef<Monaco.editor.IStandaloneCodeEditor | null>(null);
   const monacoRef = useRef<typeof Monaco | null>(null);
   const searchInputRef = useRef<HTMLInputElement | null>(null);
   const decorationsRef = useRef<string[]>([]);
+  const matchesRef = useRef<Monaco.editor.FindMatch[]>([]);
+  const targetDecorationsRef = useRef<string[]>([]);
+  /** 最後に読み込んだ / 保存したときの mtime (ms)。stat できなければ null */
+  const diskMtimeRef = useRef<number | null>(null);
+  const gitChangesRef = useRef<LineChange[]>([]);
+  const gitDecorationsRef = useRef<string[]>([]);
+  /** 0-based 行 → blame。ファイルを保存し直すまでは使い回す */
+  const blameCacheRef = useRef(new Map<number, BlameLine>());
+  const blameDecorationsRef = useRef<string[]>([]);
   const queryDebounceRef = useRef<number | null>(null);
+  /** 部分読み込み時、エディタ 1 行目がファイルの何行目か (0-based)。通常は 0 */
+  const lineOffsetRef = useRef(0);
 
   const language = useMemo(() => languageOf(path), [path]);
+  useFullscreenShortcut();
+
+  const applyFileText = useCallback(
+    (ft: FileText) => {
+      lineOffsetRef.current = ft.start_line;
+      setFileInfo({
+        lossy: ft.lossy,
+        partial: ft.partial,
+        truncated: ft.truncated,
+        startLine: ft.start_line,
+      });
+      setContents(ft.text);
+      setSavedContents(ft.text);
+      // LSP didOpen (失敗しても無視 — clangd が動いていない / non-cpp ファイル)。
+      // 一部しか読んでいないときは clangd にはディスク上の全体を読ませる
+      if (!ft.partial) void lsp.openFile(path, ft.text).catch(() => undefined);
+    },
+    [path],
+  );
 
   // 初期ロード
   useEffect(() => {
     let aborted = false;
     (async () => {
       try {
-        const text = await readTextFile(path);
+        const ft = await fs.readFileText(path, initialLine, loadMaxFileBytes());
         if (!aborted) {
-          setContents(text);
-          // LSP didOpen (失敗しても無視 — clangd が動いていない / non-cpp ファイル)
-          void lsp.openFile(path, text).catch(() => undefined);
+          diskMtimeRef.current = await readMtime(path);
+          applyFileText(ft);
         }
       } catch (e) {
-        if (!aborted) setError(String(e));
+        if (!aborted) setError(describeSnippetError(e));
       }
     })();
     return () => {
       aborted = true;
     };
-  }, [path]);
+  }, [path, initialLine, applyFileText]);
 
+  const dirty = contents !== null && contents !== savedContents;
+
+  // header のパスは末尾 (ファイル名) が切れないよう、途中のディレクトリを畳む
+  const pathRef = useRef<HTMLSpanElement | null>(null);
+  const [pathWidth, setPathWidth] = useState<number | null>(null);
+  useEffect(() => {
+    const el = pathRef.current;
+    if (!el) return;
+    const ro = new ResizeObserver(([entry]) => setPathWidth(entry.contentRect.width));
+    ro.observe(el);
+    return () => ro.disconnect();
+  }, []);
+  const shownPath = useMemo(() => {
+    const el = pathRef.current;
+    if (pathWidth === null || !el) return path;
+    const measure = domMeasure(el);
+    const room = pathWidth - (dirty ? measure("● ") : 0);
+    return ellipsizePath(path, room, measure);
+  }, [path, pathWidth, dirty]);
+  // 置換文字入り / 一部だけの本文を書き戻すとファイルを壊すので編集させない
+  const readOnly = fileInfo !== null && (fileInfo.lossy || fileInfo.partial);
+
+  // 外部での変更検知: 1 秒ごとに mtime を見るだけ (stat なので安い)
   useEffect(() => {
-    if (path) document.title = `Iter — ${path.split(/[\\/]/).pop() ?? path}`;
+    const timer = window.setInterval(async () => {
+      if (diskMtimeRef.current === null) return;
+      const mtime = await readMtime(path);
+      if (mtime !== null && mtime !== diskMtimeRef.current) setDiskChanged(true);
+    }, DISK_POLL_MS);
+    return () => window.clearInterval(timer);
   }, [path]);
 
+  const reloadFromDisk = useCallback(async () => {
+    try {
+      const ft = await fs.readFileText(path, initialLine, loadMaxFileBytes());
+      diskMtimeRef.current = await readMtime(path);
+      applyFileText(ft);
+      setDiskChanged(false);
+      // 行・桁の印は同じ行番号のまま残す。内容がずれているかもしれないことだけ知らせる
+      if (targetDecorationsRef.current.length > 0) {
+        setTargetNotice("再読み込みしたため、印の位置は古い可能性があります");
+      }
+    } catch (e) {
+      setSaveError(`再読み込みに失敗: ${describeSnippetError(e)}`);
+    }
+  }, [path, initialLine, applyFileText]);
+
+  // Alt+R: ディスクから再読み込み (変更検知の通知が出ているときだけ)。e.code で判定
+  useEffect(() => {
+    if (!diskChanged) return;
+    const onKey = (e: KeyboardEvent) => {
+      if (e.altKey && !e.ctrlKey && !e.metaKey && e.code === "KeyR") {
+        e.preventDefault();
+        void reloadFromDisk();
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [diskChanged, reloadFromDisk]);
+
+  // HEAD からの変更行を gutter に出す。decoration は編集に追従して動くので、
+  // 取り直すのはディスク上の内容が変わったとき (読み込み / 保存) だけ
+  const applyGitChanges = useCallback(() => {
+    const ed = editorRef.current;
+    const monaco = monacoRef.current;
+    if (!ed || !monaco) return;
+    gitDecorationsRef.current = ed.deltaDecorations(
+      gitDecorationsRef.current,
+      gitChangesRef.current.map((c) => ({
+        range: {
+          startLineNumber: c.start_line + 1,
+          startColumn: 1,
+          endLineNumber: c.start_line + c.line_count,
+          endColumn: 1,
+        },
+        options: {
+          isWholeLine: true,
+          linesDecorationsClassName: `iter-git-${c.kind}`,
+          overviewRuler: {
+            color: GIT_RULER_COLOR[c.kind],
+            position: monaco.editor.OverviewRulerLane.Left,
+          },
+        },
+      })),
+    );
+  }, []);
+
+  useEffect(() => {
+    // 一部しか読んでいないと行番号が diff と合わないので出さない
+    if (savedContents === null || fileInfo?.partial) return;
+    let aborted = false;
+    git
+      .lineChanges(path)
+      // リポジトリ外 / git 無しなどは gutter を出さないだけ
+      .catch((): LineChange[] => [])
+      .then((changes) => {
+        if (aborted) return;
+        gitChangesRef.current = changes;
+        applyGitChanges();
+      });
+    return () => {
+      aborted = true;
+    };
+  }, [path, savedContents, fileInfo?.partial, applyGitChanges]);
+
+  useEffect(() => {
+    if (!path) return;
+    const name = path.split(/[\\/]/).pop() ?? path;
+    document.title = `${dirty ? "● " : ""}Iter — ${name}`;
+  }, [path, dirty]);
+
   // Control Panel の relation toggle を listen
   useEffect(() => {
     let unlisten: UnlistenFn | null = null;
@@ -109,6 +366,49 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
     };
   }, []);
 
+  // 0-based の行 / 桁へスクロールし、行全体と桁位置の 1 文字に印を付ける
+  const revealTarget = useCallback((line: number, column: number) => {
+    const ed = editorRef.current;
+    const monaco = monacoRef.current;
+    const model = ed?.getModel();
+    if (!ed || !monaco || !model) return;
+    const lineCount = model.getLineCount();
+    // line はファイル上の行。部分読み込みならエディタ上の行へずらす
+    const offset = lineOffsetRef.current;
+    const local = line - offset;
+    // 古い位置情報 (編集で行が減った等) は末尾行へ寄せ、その旨を header に出す
+    setTargetNotice(
+      local + 1 > lineCount
+        ? offset === 0
+          ? `${line + 1} 行目はもうありません (ファイルは ${lineCount} 行)`
+          : `${line + 1} 行目は読み込んだ範囲にありません`
+        : null,
+    );
+    const lineNumber = Math.min(Math.max(1, local + 1), lineCount);
+    // 行末より先を指す桁 (古い位置情報など) は行末に寄せる
+    const col = Math.min(Math.max(1, column + 1), model.getLineMaxColumn(lineNumber));
+    ed.revealLineInCenter(lineNumber);
+    ed.setPosition({ lineNumber, column: col });
+    targetDecorationsRef.current = ed.deltaDecorations(targetDecorationsRef.current, [
+      {
+        range: { startLineNumber: lineNumber, startColumn: 1, endLineNumber: lineNumber, endColumn: 1 },
+        options: {
+          isWholeLine: true,
+          className: "iter-target-line",
+          overviewRuler: {
+            color: TARGET_RULER_COLOR,
+            position: monaco.editor.OverviewRulerLane.Full,
+          },
+        },
+      },
+      {
+        range: { startLineNumber: lineNumber, startColumn: col, endLineNumber: lineNumber, endColumn: col + 1 },
+        options: { inlineClassName: "iter-target-col" },
+      },
+    ]);
+    ed.focus();
+  }, []);
+
   // open-at イベント (別ウィンドウから飛ばされてくる)
   useEffect(() => {
     let unlisten: UnlistenFn | null = null;
@@ -119,22 +419,15 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
         column: number;
         follow_definition: boolean;
       }>("iter://open-at", (e) => {
-        const ed = editorRef.current;
-        if (!ed) return;
         if (e.payload.path === path) {
-          ed.revealLineInCenter(e.payload.line + 1);
-          ed.setPosition({
-            lineNumber: e.payload.line + 1,
-            column: (e.payload.column || 0) + 1,
-          });
-          ed.focus();
+          revealTarget(e.payload.line, e.payload.column || 0);
         }
       });
     })();
     return () => {
       unlisten?.();
     };
-  }, [path]);
+  }, [path, revealTarget]);
 
   // Ctrl+F → 検索フォーカス
   useEffect(() => {
@@ -149,6 +442,29 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
     return () => window.removeEventListener("keydown", onKey);
   }, []);
 
+  // 検索ヒットの装飾。current 番目だけ別色にして、そこへスクロールする
+  const showMatches = useCallback((current: number) => {
+    const ed = editorRef.current;
+    const monaco = monacoRef.current;
+    if (!ed || !monaco) return;
+    const matches = matchesRef.current;
+    decorationsRef.current = ed.deltaDecorations(
+      decorationsRef.current,
+      matches.map((m, i) => ({
+        range: m.range,
+        options: {
+          inlineClassName: i === current ? "iter-search-hit-current" : "iter-search-hit",
+          // スクロールバー上にもヒット位置を出す (ファイルのどこに散っているか一目で分かるように)
+          overviewRuler: {
+            color: i === current ? SEARCH_CURRENT_RULER_COLOR : SEARCH_HIT_RULER_COLOR,
+            position: monaco.editor.OverviewRulerLane.Center,
+          },
+        },
+      })),
+    );
+    if (matches[current]) ed.revealRangeInCenter(matches[current].range);
+  }, []);
+
   // 検索文字列のハイライト
   useEffect(() => {
     const ed = editorRef.current;
@@ -158,22 +474,41 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
     if (!model) return;
 
     if (!searchTerm) {
+      matchesRef.current = [];
       ed.deltaDecorations(decorationsRef.current, []);
       decorationsRef.current = [];
       setMatchCount(null);
+      setMatchIndex(0);
       return;
     }
-    const matches = model.findMatches(searchTerm, true, false, false, null, false);
-    setMatchCount(matches.length);
-    decorationsRef.current = ed.deltaDecorations(
-      decorationsRef.current,
-      matches.map((m) => ({
-        range: m.range,
-        options: { inlineClassName: "iter-search-hit" },
-      })),
-    );
-    if (matches.length > 0) ed.revealRangeInCenter(matches[0].range);
-  }, [searchTerm]);
+    matchesRef.current = model.findMatches(searchTerm, true, false, false, null, false);
+    setMatchCount(matchesRef.current.length);
+    setMatchIndex(0);
+    showMatches(0);
+  }, [searchTerm, showMatches]);
+
+  /** delta = +1 で次、-1 で前のヒットへ (端で折り返す) */
+  const stepMatch = useCallback(
+    (delta: number) => {
+      const n = matchesRef.current.length;
+      if (n === 0) return;
+      const next = (matchIndex + delta + n) % n;
+      setMatchIndex(next);
+      showMatches(next);
+    },
+    [matchIndex, showMatches],
+  );
+
+  const onSearchKeyDown = (e: ReactKeyboardEvent<HTMLInputElement>) => {
+    if (e.key === "Enter") {
+      e.preventDefault();
+      stepMatch(e.shiftKey ? -1 : 1);
+    } else if (e.key === "Escape") {
+      e.preventDefault();
+      setSearchTerm("");
+      editorRef.current?.focus();
+    }
+  };
 
   // カーソル位置 (1-based monaco) に応じて LSP を叩いて relation 更新
   const queryRelations = useCallback(
@@ -181,7 +516,8 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
       if (queryDebounceRef.current !== null)
         window.clearTimeout(queryDebounceRef.current);
       queryDebounceRef.current = window.setTimeout(() => {
-        const line = Math.max(0, lineMonaco - 1); // → LSP 0-based
+        // → LSP 0-based (部分読み込みならファイル上の行に戻す)
+        const line = Math.max(0, lineMonaco - 1) + lineOffsetRef.current;
         const character = Math.max(0, colMonaco - 1);
         const wantHierarchy = showRelations.callers || showRelations.callees;
         const wantRefs = showRelations.references;
@@ -209,13 +545,10 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
   const handleMount: OnMount = (editor, monaco) => {
     editorRef.current = editor;
     monacoRef.current = monaco;
+    applyGitChanges();
 
     if (initialLine !== undefined) {
-      const ln = Math.max(1, initialLine);
-      const col = (initialCol ?? 0) + 1;
-      editor.revealLineInCenter(ln);
-      editor.setPosition({ lineNumber: ln, column: col });
-      editor.focus();
+      revealTarget(initialLine, initialCol ?? 0);
       if (followDefinition) {
         // 宣言を追跡: 最初に LSP definitions を取り、結果が別ファイルなら open_at
         // (Phase 2 では definitions を未公開なので references の最初を流用)
@@ -231,12 +564,23 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
   const save = useCallback(async () => {
     const ed = editorRef.current;
     if (!ed) return;
+    if (readOnly) {
+      setSaveError("一部だけ / 文字を置換して読み込んだため保存できません");
+      return;
+    }
+    const text = ed.getValue();
     try {
-      await writeTextFile(path, ed.getValue());
+      await writeTextFile(path, text);
+      // 自分の保存で「外部で変更された」と誤検知しないよう mtime を取り直す
+      diskMtimeRef.current = await readMtime(path);
+      setDiskChanged(false);
+      setSavedContents(text);
+      setSaveError(null);
     } catch (e) {
-      setError(`保存に失敗: ${String(e)}`);
+      // エディタは残したまま header に出す (setError だと編集内容が見えなくなる)
+      setSaveError(`保存に失敗: ${String(e)}`);
     }
-  }, [path]);
+  }, [path, readOnly]);
 
   useEffect(() => {
     const onKey = (e: KeyboardEvent) => {
@@ -249,6 +593,179 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
     return () => window.removeEventListener("keydown", onKey);
   }, [save]);
 
+  const cycleGraphLayout = useCallback(() => {
+    setGraphLayout((prev) => {
+      const next = GRAPH_LAYOUTS[(GRAPH_LAYOUTS.indexOf(prev) + 1) % GRAPH_LAYOUTS.length];
+      try {
+        localStorage.setItem(GRAPH_LAYOUT_KEY, next);
+      } catch {
+        // 保存できなくても切替自体は有効
+      }
+      return next;
+    });
+  }, []);
+
+  // Ctrl+Shift+G: 関連グラフの配置 (左→右 / 上→下 / 放射) を切替
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if ((e.ctrlKey || e.metaKey) && e.shiftKey && e.key.toLowerCase() === "g") {
+        e.preventDefault();
+        cycleGraphLayout();
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [cycleGraphLayout]);
+
+  const toggleEdgeRouting = useCallback(() => {
+    setEdgeRouting((prev) => {
+      const next = prev === "orthogonal" ? "curved" : "orthogonal";
+      try {
+        localStorage.setItem(EDGE_ROUTING_KEY, next);
+      } catch {
+        // 保存できなくても切替自体は有効
+      }
+      return next;
+    });
+  }, []);
+
+  // Ctrl+Shift+E: 関連グラフのエッジを曲線 / 直角の折れ線で切替
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if ((e.ctrlKey || e.metaKey) && e.shiftKey && e.key.toLowerCase() === "e") {
+        e.preventDefault();
+        toggleEdgeRouting();
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [toggleEdgeRouting]);
+
+  const toggleWordWrap = useCallback(() => {
+    setWordWrap((prev) => {
+      try {
+        localStorage.setItem(WORD_WRAP_KEY, prev ? "off" : "on");
+      } catch {
+        // 保存できなくても切替自体は有効
+      }
+      return !prev;
+    });
+  }, []);
+
+  // blame は HEAD と保存済みの内容で決まるので、保存したら取り直す
+  useEffect(() => {
+    blameCacheRef.current.clear();
+  }, [path, savedContents]);
+
+  const renderBlame = useCallback(() => {
+    const ed = editorRef.current;
+    if (!ed) return;
+    const now = Date.now() / 1000;
+    blameDecorationsRef.current = ed.deltaDecorations(
+      blameDecorationsRef.current,
+      [...blameCacheRef.current.values()].map((b) => ({
+        range: {
+          startLineNumber: b.line + 1,
+          startColumn: 1,
+          endLineNumber: b.line + 1,
+          endColumn: 1,
+        },
+        options: { before: { content: blameLabel(b, now), inlineClassName: "iter-blame" } },
+      })),
+    );
+  }, []);
+
+  // 表示中の範囲 (+ 前後 BLAME_MARGIN 行) のうち未取得の行だけ git blame する。
+  // 結果が返るまでに blame を OFF にしたり保存し直したりしていたら (`isActive()` が
+  // false)、古い結果なので捨てる
+  const fetchVisibleBlame = useCallback(async (isActive: () => boolean) => {
+    const ed = editorRef.current;
+    const model = ed?.getModel();
+    if (!ed || !model) return;
+    const visible = ed.getVisibleRanges();
+    if (visible.length === 0) return;
+    const first = Math.max(0, visible[0].startLineNumber - 1 - BLAME_MARGIN);
+    const last = Math.min(
+      model.getLineCount(),
+      visible[visible.length - 1].endLineNumber + BLAME_MARGIN,
+    );
+    const cache = blameCacheRef.current;
+    let lo = first;
+    while (lo < last && cache.has(lo)) lo++;
+    let hi = last;
+    while (hi > lo && cache.has(hi - 1)) hi--;
+    if (lo >= hi) return;
+    try {
+      const lines = await git.blame(path, lo, hi);
+      if (!isActive()) return;
+      for (const b of lines) cache.set(b.line, b);
+      renderBlame();
+    } catch (e) {
+      if (!isActive()) return;
+      setBlameOn(false);
+      setBlameNotice(`blame を表示できません: ${String(e)}`);
+    }
+  }, [path, renderBlame]);
+
+  useEffect(() => {
+    const ed = editorRef.current;
+    if (!blameOn || !ed) return;
+    // 未保存の編集があると blame の行が本文とずれるので、保存するまで隠す
+    if (dirty) {
+      setBlameNotice("未保存の変更があるため blame を隠しています (保存すると再表示)");
+      return () => setBlameNotice(null);
+    }
+    setBlameNotice(null);
+    renderBlame();
+    let active = true;
+    const isActive = () => active;
+    void fetchVisibleBlame(isActive);
+    let timer: number | null = null;
+    const sub = ed.onDidScrollChange(() => {
+      if (timer !== null) window.clearTimeout(timer);
+      timer = window.setTimeout(() => void fetchVisibleBlame(isActive), 150);
+    });
+    return () => {
+      active = false;
+      sub.dispose();
+      if (timer !== null) window.clearTimeout(timer);
+      blameDecorationsRef.current = ed.deltaDecorations(blameDecorationsRef.current, []);
+    };
+  }, [blameOn, dirty, savedContents, fetchVisibleBlame, renderBlame]);
+
+  const toggleBlame = useCallback(() => {
+    if (fileInfo?.partial) {
+      setBlameNotice("一部だけ読み込んだファイルでは blame を表示できません");
+      return;
+    }
+    setBlameOn((v) => !v);
+  }, [fileInfo?.partial]);
+
+  // Alt+B: blame 列の表示切替 (Alt+Z と同じく e.code で判定)
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.altKey && !e.ctrlKey && !e.metaKey && e.code === "KeyB") {
+        e.preventDefault();
+        toggleBlame();
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [toggleBlame]);
+
+  // Alt+Z: 長い行の折り返し切替 (VS Code と同じキー)。
+  // macOS の Option+Z は e.key が "Ω" になるため、物理キーの e.code で判定する
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.altKey && !e.ctrlKey && !e.metaKey && e.code === "KeyZ") {
+        e.preventDefault();
+        toggleWordWrap();
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [toggleWordWrap]);
+
   // Ctrl+Shift+W: 自分以外の File Window を全部閉じる
   useEffect(() => {
     const onKey = async (e: KeyboardEvent) => {
@@ -286,19 +803,77 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
   return (
     <div className="fw-shell-2">
       <header className="fw-header">
-        <span className="fw-path" title={path}>
-          {path}
+        <span className="fw-path" title={path} ref={pathRef}>
+          {dirty && (
+            <span className="fw-dirty" title="未保存の変更があります (Ctrl+S で保存)">
+              ●{" "}
+            </span>
+          )}
+          {shownPath}
         </span>
+        {fileInfo?.partial && (
+          <span
+            className="fw-target-notice"
+            title="サイズ上限 (Control Panel の設定「読み込み上限」) を超えたため、対象行の周辺だけ読み込んでいます。編集・保存はできません"
+          >
+            一部のみ読み込み ({fileInfo.startLine + 1} 行目〜)
+          </span>
+        )}
+        {fileInfo?.truncated && (
+          <span
+            className="fw-target-notice"
+            title="読み込んだ範囲もサイズ上限を超えたため、途中の行で切っています"
+          >
+            途中で打ち切り
+          </span>
+        )}
+        {fileInfo?.lossy && (
+          <span
+            className="fw-target-notice"
+            title="UTF-8 として読めないバイトを � に置き換えて表示しています。編集・保存はできません"
+          >
+            文字コード置換あり
+          </span>
+        )}
+        {diskChanged && (
+          <span className="fw-disk-changed">
+            ディスク上で変更されました
+            <button onClick={() => void reloadFromDisk()} title="Alt+R">
+              {dirty ? "再読み込み (未保存の変更は破棄)" : "再読み込み"}
+            </button>
+          </span>
+        )}
+        {blameNotice && (
+          <span className="fw-target-notice" title={blameNotice}>
+            {blameNotice}
+          </span>
+        )}
+        {targetNotice && (
+          <span className="fw-target-notice" title={targetNotice}>
+            {targetNotice}
+          </span>
+        )}
+        {saveError && (
+          <span className="fw-save-error" title={saveError}>
+            {saveError}
+          </span>
+        )}
         <div className="fw-search">
           <input
             ref={searchInputRef}
             type="search"
             placeholder="検索 (Ctrl+F)"
+            title="Enter: 次 / Shift+Enter: 前 / Esc: 解除"
             value={searchTerm}
             onChange={(e) => setSearchTerm(e.target.value)}
+            onKeyDown={onSearchKeyDown}
           />
           <span className="count">
-            {matchCount === null ? "" : `${matchCount} 件`}
+            {matchCount === null
+              ? ""
+              : matchCount === 0
+                ? "0 件"
+                : `${matchIndex + 1} / ${matchCount} 件`}
           </span>
           <button
             onClick={() => setGraphCollapsed((v) => !v)}
@@ -306,6 +881,35 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
           >
             {graphCollapsed ? "▸ Graph" : "▾ Graph"}
           </button>
+          <button
+            onClick={cycleGraphLayout}
+            title={`関連グラフの配置: ${graphLayout} (Ctrl+Shift+G で切替)`}
+            disabled={graphCollapsed}
+          >
+            {GRAPH_LAYOUT_ICON[graphLayout]}
+          </button>
+          <button
+            onClick={toggleEdgeRouting}
+            title={`関連グラフのエッジ: ${edgeRouting} (Ctrl+Shift+E で切替、放射配置では直線)`}
+            disabled={graphCollapsed || graphLayout === "radial"}
+            aria-pressed={edgeRouting === "orthogonal"}
+          >
+            {edgeRouting === "orthogonal" ? "┘" : "∿"}
+          </button>
+          <button
+            onClick={toggleWordWrap}
+            title="長い行の折り返し (Alt+Z)"
+            aria-pressed={wordWrap}
+          >
+            {wordWrap ? "折返し: ON" : "折返し: OFF"}
+          </button>
+          <button
+            onClick={toggleBlame}
+            title="git blame を行頭に表示 (Alt+B)"
+            aria-pressed={blameOn}
+          >
+            {blameOn ? "Blame: ON" : "Blame: OFF"}
+          </button>
           <button onClick={save} title="Ctrl+S">
             保存
           </button>
@@ -331,7 +935,8 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
           ) : (
             <Editor
               height="100%"
-              theme="vs-dark"
+              theme={editorTheme}
+              beforeMount={defineIterTheme}
               language={language}
               value={contents}
               onChange={(v) => setContents(v ?? "")}
@@ -340,9 +945,23 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
                 automaticLayout: true,
                 minimap: { enabled: true },
                 fontSize: 13,
-                wordWrap: "off",
+                // タブの表示幅はカードと揃える。中身はタブのまま保持される
+                tabSize,
+                readOnly,
+                // 部分読み込みではファイル上の行番号を出す
+                lineNumbers: fileInfo?.startLine
+                  ? (n: number) => String(n + fileInfo.startLine)
+                  : "on",
+                // 「expected '}'」系のエラーで対応を追えるよう、括弧は常に対で強調し
+                // 入れ子の深さごとに色分けする (対応の無い閉じ括弧は赤)
+                matchBrackets: "always",
+                bracketPairColorization: { enabled: true },
+                guides: { bracketPairs: "active" },
+                wordWrap: wordWrap ? "on" : "off",
                 renderWhitespace: "selection",
                 smoothScrolling: true,
+                // 長い関数の途中でも外側の関数 / class の宣言行を上端に残す (クリックでその行へ)
+                stickyScroll: { enabled: true, maxLineCount: 3 },
                 mouseWheelZoom: true,
               }}
             />
@@ -354,12 +973,44 @@ export function FileWindow({ path, initialLine, initialCol, followDefinition }:
               data={relationData}
               show={showRelations}
               projectRoot={projectRoot}
+              layout={graphLayout}
+              routing={edgeRouting}
             />
           </div>
         )}
       </div>
       <style>{`
         .iter-search-hit { background: rgba(74, 122, 254, 0.35); border-radius: 2px; }
+        .iter-git-added, .iter-git-modified {
+          margin-left: 3px;
+          width: 3px !important;
+        }
+        .iter-git-added { background: #3fb950; }
+        .iter-git-modified { background: #4a7afe; }
+        .iter-git-removed::after {
+          content: "";
+          position: absolute;
+          left: 3px;
+          bottom: -4px;
+          border-left: 5px solid #e66060;
+          border-top: 4px solid transparent;
+          border-bottom: 4px solid transparent;
+        }
+        .iter-blame {
+          display: inline-block;
+          width: 15ch;
+          margin-right: 1ch;
+          color: #4a4f5a;
+          font-style: normal;
+        }
+        .iter-target-line { background: rgba(74, 122, 254, 0.12); }
+        .iter-target-col {
+          border-bottom: 2px solid #efc56a;
+        }
+        .iter-search-hit-current {
+          background: rgba(239, 197, 106, 0.55);
+          border-radius: 2px;
+        }
         .fw-shell-2 {
           display: grid; grid-template-rows: auto 1fr; height: 100%;
         }
@@ -0,0 +1,30 @@
+import { describe, it, expect, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { cardBodyLines, snippetFields } from "./RelationCard";
+
+describe("snippetFields", () => {
+  it("spreads a snippet into the card fields", () => {
+    const s = { start_line: 8, target_line: 10, total_lines: 40, lines: ["a", "b", "c"] };
+    expect(snippetFields(s, 10)).toEqual({
+      snippet: ["a", "b", "c"],
+      snippetStart: 8,
+      totalLines: 40,
+    });
+    expect(cardBodyLines(s)).toBe(3);
+  });
+
+  it("carries the failure message and keeps the target line as the start", () => {
+    const f = { error: "ファイルが見つかりません: /p/gone.cpp" };
+    expect(snippetFields(f, 10)).toEqual({
+      snippet: [],
+      snippetStart: 10,
+      error: "ファイルが見つかりません: /p/gone.cpp",
+    });
+    // 失敗カードも本文 2 行分の高さで見積もる
+    expect(cardBodyLines(f)).toBe(2);
+  });
+});
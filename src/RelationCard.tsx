@@ -4,6 +4,7 @@
  * - Monaco を使わず、軽量な `<pre>` で前後 N 行のコードを表示
  * - クリックで in-project なら `open_at`、外なら no-op
  * - variant prop で badge 色とラベルを差し替え (caller / callee / reference / frame / custom)
+ * - 左端の細い帯はファイルごとの色 (`fileColor`)。同じファイルのカードが揃う
  *
  * 設計方針:
  *   - DOM は header (1 行) + body (snippet) のシンプル 2 行構成
@@ -13,7 +14,8 @@
  */
 import { memo } from "react";
 import { Handle, Position, type NodeProps } from "@xyflow/react";
-import { win } from "./lsp";
+import { fileColor } from "./fileColor";
+import { win, type Snippet, type SnippetFailure } from "./lsp";
 
 export type RelationVariant =
   | "caller"
@@ -32,14 +34,22 @@ export interface RelationCardData {
   path: string;
   /** 0-based の対象行 */
   line: number;
+  /** 0-based の対象桁。不明なら行頭 */
+  column?: number;
   /** 取得済みのスニペット行 (前後 ±context) */
   snippet: string[];
   /** snippet の最初の行が file 中の何行目か (0-based) */
   snippetStart: number;
+  /** ファイルの総行数。line がこれ以上なら対象行はもう存在しない (編集で縮んだ等) */
+  totalLines?: number;
+  /** スニペットを読めなかった理由。あれば本文の代わりに表示する */
+  error?: string;
   /** プロジェクト外なら disabled (クリック不可、文字色グレー) */
   inProject: boolean;
   /** 1 行 badge (caller/callee/refs/#0 等)。variant の自動値を上書きしたい時に使う */
   badge?: string;
+  /** エッジの接続点をカード中央に置く (放射配置用。辺の向きが一定でないため) */
+  centerHandles?: boolean;
   [key: string]: unknown;
 }
 
@@ -68,11 +78,18 @@ export const RelationCard = memo(function RelationCard(
   const accent = ACCENT_BY_VARIANT[data.variant] ?? ACCENT_BY_VARIANT.custom;
   const label = data.badge ?? LABEL_BY_VARIANT[data.variant];
   const fname = data.path.split(/[\\/]/).pop() ?? data.path;
-  const fileTitle = `${fname}:${data.line + 1}`;
+  const stale = data.totalLines !== undefined && data.line >= data.totalLines;
+  const fileTitle = stale
+    ? `${fname}:${data.line + 1} (ファイルは ${data.totalLines} 行)`
+    : `${fname}:${data.line + 1}`;
+
+  const handleStyle = data.centerHandles
+    ? { opacity: 0, top: "50%", left: "50%" }
+    : { opacity: 0 };
 
   const handleClick = () => {
     if (!data.inProject) return;
-    void win.openAt(data.path, data.line, 0, false);
+    void win.openAt(data.path, data.line, data.column ?? 0, false);
   };
 
   return (
@@ -81,35 +98,109 @@ export const RelationCard = memo(function RelationCard(
       className={data.inProject ? "iter-card iter-clickable" : "iter-card iter-disabled"}
       style={{
         ["--card-accent" as string]: accent,
+        ["--card-file" as string]: fileColor(data.path),
       }}
       title={data.path}
     >
-      <Handle type="target" position={Position.Left} style={{ opacity: 0 }} />
+      <Handle
+        type="target"
+        position={props.targetPosition ?? Position.Left}
+        style={handleStyle}
+      />
       <div className="iter-card-head">
         {label && <span className="iter-card-badge">{label}</span>}
         <span className="iter-card-symbol">{data.symbol ?? "<anon>"}</span>
-        <span className="iter-card-loc">{fileTitle}</span>
+        <span className={stale ? "iter-card-loc stale" : "iter-card-loc"}>{fileTitle}</span>
       </div>
-      <pre className="iter-card-body">
-        {data.snippet.map((s, i) => {
-          const ln = data.snippetStart + i;
-          const isTarget = ln === data.line;
-          return (
-            <div
-              key={i}
-              className={isTarget ? "iter-card-line target" : "iter-card-line"}
-            >
-              <span className="iter-card-lineno">{ln + 1}</span>
-              <span className="iter-card-linetext">{s || " "}</span>
-            </div>
-          );
-        })}
-      </pre>
-      <Handle type="source" position={Position.Right} style={{ opacity: 0 }} />
+      {data.error ? (
+        <div className="iter-card-error">{data.error}</div>
+      ) : (
+        <pre className="iter-card-body">
+          {data.snippet.map((s, i) => {
+            const ln = data.snippetStart + i;
+            const isTarget = ln === data.line;
+            return (
+              <div
+                key={i}
+                className={isTarget ? "iter-card-line target" : "iter-card-line"}
+              >
+                <span className="iter-card-lineno">{ln + 1}</span>
+                <span className="iter-card-linetext">{s || " "}</span>
+              </div>
+            );
+          })}
+        </pre>
+      )}
+      <Handle
+        type="source"
+        position={props.sourcePosition ?? Position.Right}
+        style={handleStyle}
+      />
     </div>
   );
 });
 
+// 以下は ensureCardStyles の寸法と揃えること (レイアウトがカード高さの推定に使う)
+const CARD_HEAD_HEIGHT = 23;
+const CARD_LINE_HEIGHT = 16; // 11px * line-height 1.45
+const CARD_BODY_MAX_HEIGHT = 130;
+const CARD_BODY_PADDING = 8;
+const CARD_BORDER = 2;
+
+/** 読み込み失敗カードの本文 (理由の文言) は 2 行分として見積もる */
+const CARD_ERROR_LINES = 2;
+
+/**
+ * `readSnippetOrFailure` の結果をカードの snippet 系フィールドに展開する。
+ * `line` は取得できなかったときの snippetStart の代わり。
+ */
+export function snippetFields(
+  s: Snippet | SnippetFailure,
+  line: number,
+): Pick<RelationCardData, "snippet" | "snippetStart" | "totalLines" | "error"> {
+  if ("error" in s) return { snippet: [], snippetStart: line, error: s.error };
+  return { snippet: s.lines, snippetStart: s.start_line, totalLines: s.total_lines };
+}
+
+/** estimateCardHeight に渡す本文の行数 */
+export function cardBodyLines(s: Snippet | SnippetFailure): number {
+  return "error" in s ? CARD_ERROR_LINES : s.lines.length;
+}
+
+/** スニペット行数から描画後のカード高さ (px) を推定する。 */
+export function estimateCardHeight(lineCount: number): number {
+  const body = Math.min(CARD_BODY_MAX_HEIGHT, lineCount * CARD_LINE_HEIGHT);
+  return CARD_HEAD_HEIGHT + body + CARD_BODY_PADDING + CARD_BORDER;
+}
+
+/** タブ幅の設定キー。FileWindow の Monaco とカードの両方がこれを読む */
+export const TAB_SIZE_KEY = "iter:tab_size";
+const DEFAULT_TAB_SIZE = 4;
+
+/** localStorage のタブ幅 (1〜16)。未設定や不正値なら 4。 */
+export function loadTabSize(): number {
+  try {
+    const n = Number(localStorage.getItem(TAB_SIZE_KEY));
+    return Number.isInteger(n) && n >= 1 && n <= 16 ? n : DEFAULT_TAB_SIZE;
+  } catch {
+    return DEFAULT_TAB_SIZE;
+  }
+}
+
+/** 1 つのグラフに並べるカードの上限の設定キー (関連グラフ / スタックトレース共通) */
+export const MAX_CARDS_KEY = "iter:max_cards";
+const DEFAULT_MAX_CARDS = 100;
+
+/** localStorage のカード上限 (正の整数)。未設定や不正値なら 100。 */
+export function loadMaxCards(): number {
+  try {
+    const n = Number(localStorage.getItem(MAX_CARDS_KEY));
+    return Number.isInteger(n) && n > 0 ? n : DEFAULT_MAX_CARDS;
+  } catch {
+    return DEFAULT_MAX_CARDS;
+  }
+}
+
 /** 全 RelationCard の共通スタイルを 1 度だけ document に流し込む。 */
 export function ensureCardStyles() {
   const id = "iter-card-style";
@@ -127,6 +218,18 @@ export function ensureCardStyles() {
       color: #c9d3e0;
       overflow: hidden;
       box-shadow: 0 1px 2px rgba(0,0,0,0.4);
+      position: relative;
+    }
+    /* ファイルごとの色の帯。枠線 (variant 色) の内側に重ねる */
+    .iter-card::before {
+      content: "";
+      position: absolute;
+      left: 0;
+      top: 0;
+      bottom: 0;
+      width: 3px;
+      background: var(--card-file, transparent);
+      z-index: 1;
     }
     .iter-clickable { cursor: pointer; }
     .iter-clickable:hover { background: #14181f; }
@@ -136,7 +239,14 @@ export function ensureCardStyles() {
       align-items: center;
       gap: 0.4rem;
       padding: 4px 8px;
+      /* variant の色からうっすら抜けるグラデーション (本文側は従来どおり単色)。
+         color-mix 非対応の WebView では下の宣言ごと無視されるので従来の単色を先に置く */
       background: rgba(255,255,255,0.03);
+      background: linear-gradient(
+        90deg,
+        color-mix(in srgb, var(--card-accent, #4a7afe) 22%, transparent),
+        rgba(255,255,255,0.03) 70%
+      );
       border-bottom: 1px solid rgba(255,255,255,0.06);
       font-size: 11px;
     }
@@ -164,6 +274,17 @@ export function ensureCardStyles() {
       font-size: 10px;
       flex-shrink: 0;
     }
+    .iter-card-loc.stale { color: #efc56a; }
+    .iter-card-error {
+      padding: 4px 8px;
+      line-height: 1.45;
+      color: #e66060;
+      background: #0e0f12;
+      white-space: normal;
+      overflow-wrap: anywhere;
+      max-height: 32px;
+      overflow: hidden;
+    }
     .iter-card-body {
       margin: 0;
       padding: 4px 0;
@@ -177,6 +298,8 @@ export function ensureCardStyles() {
       padding: 0 8px;
       line-height: 1.45;
       white-space: pre;
+      /* ブラウザ既定の 8 だとタブインデントのコードが右へ流れる */
+      tab-size: ${loadTabSize()};
     }
     .iter-card-line.target {
       background: rgba(74, 122, 254, 0.15);
@@ -4,18 +4,50 @@
  * 軽量に表示するだけ。クリックで `open_at` → 該当行を Monaco で開く。
  *
  * 100 件超の対策:
- *   - 合計表示数を 100 上限にトリム (`MAX_TOTAL_CARDS`)
+ *   - 合計表示数を上限 (既定 100、`iter:max_cards` で変更可) にトリム
  *   - スニペット取得時のコンテキスト行 (`SNIPPET_CONTEXT`) も負荷に応じて自動で
  *     2 行まで減らす (探索範囲短縮)
  *   - 切り捨て分の件数は subtitle で表示
  */
-import { useEffect, useMemo, useState } from "react";
-import { ReactFlow, Background, Controls, type Node, type Edge } from "@xyflow/react";
+import { useEffect, useMemo, useRef, useState } from "react";
+import {
+  ReactFlow,
+  Background,
+  Controls,
+  MarkerType,
+  Position,
+  useNodesState,
+  type Node,
+  type Edge,
+  type ReactFlowInstance,
+} from "@xyflow/react";
 import "@xyflow/react/dist/style.css";
-import { fs, type CallHierarchyResult, type LspLocation, uriToPath } from "./lsp";
+import { BorderEdge } from "./BorderEdge";
+import {
+  fs,
+  type CallHierarchyResult,
+  type LspLocation,
+  type Snippet,
+  type SnippetFailure,
+  uriToPath,
+} from "./lsp";
+import {
+  centeredOffsets,
+  easeInOutCubic,
+  edgeStrokeWidth,
+  lerpXY,
+  ringPoints,
+  ringRadius,
+  stackOffsets,
+  type XY,
+} from "./layout";
 import {
   RelationCard,
+  cardBodyLines,
   ensureCardStyles,
+  estimateCardHeight,
+  loadMaxCards,
+  snippetFields,
   type RelationCardData,
   type RelationVariant,
 } from "./RelationCard";
@@ -26,37 +58,72 @@ export interface RelationData {
   references: LspLocation[];
 }
 
+/**
+ * 配置方式。horizontal / vertical は層 (caller → origin → callee) を左→右 /
+ * 上→下に並べる。radial は origin を中心にした 1 周の円周上に並べる。
+ */
+export type RelationLayout = "horizontal" | "vertical" | "radial";
+
+/**
+ * エッジの引き方。curved は React Flow 既定のベジェ、orthogonal は層の間の
+ * 通路を縦横の折れ線 (角は丸める) で結ぶ。radial には層が無いので常に直線。
+ */
+export type EdgeRouting = "curved" | "orthogonal";
+
 interface Props {
   data: RelationData | null;
   show: { callers: boolean; callees: boolean; references: boolean };
   projectRoot: string | null;
+  layout?: RelationLayout;
+  routing?: EdgeRouting;
 }
 
-const MAX_TOTAL_CARDS = 100;
 const DEFAULT_SNIPPET_CONTEXT = 5;
 const REDUCED_SNIPPET_CONTEXT = 2;
 
 const CARD_WIDTH = 340;
-const CARD_HEIGHT = 180;
 const COL_GAP = 80;
 const ROW_GAP = 30;
+/** origin と references 列の間隔 */
+const REF_GAP = 80;
+/** 配置切替時にカードが新しい位置へ移動する時間 */
+const LAYOUT_ANIM_MS = 400;
+/** 移動後に全体を画面へ収め直す時間 */
+const FIT_ANIM_MS = 200;
+/** これより縮小したらエッジのラベル (calls / refs) を出さない (読めずに線を隠すだけなので) */
+const EDGE_LABEL_MIN_ZOOM = 0.5;
 
 const nodeTypes = { card: RelationCard };
+const edgeTypes = { border: BorderEdge };
 
 interface RawTarget {
   variant: RelationVariant;
   symbol: string | null;
   path: string;
   line: number;
+  column: number;
   inProject: boolean;
+  /** 呼び出し箇所の数 (callHierarchy の fromRanges)。reference は 1 */
+  calls: number;
+}
+
+interface FetchedSnippets {
+  data: RelationData;
+  targets: RawTarget[];
+  originSnippet: Snippet | SnippetFailure;
+  snippets: (Snippet | SnippetFailure)[];
+  trimmed: number;
 }
 
-export function RelationGraph({ data, show, projectRoot }: Props) {
-  const [graph, setGraph] = useState<{ nodes: Node[]; edges: Edge[]; trimmed: number }>({
-    nodes: [],
-    edges: [],
-    trimmed: 0,
-  });
+export function RelationGraph({
+  data,
+  show,
+  projectRoot,
+  layout = "horizontal",
+  routing = "curved",
+}: Props) {
+  const [fetched, setFetched] = useState<FetchedSnippets | null>(null);
+  const maxCards = useMemo(loadMaxCards, []);
 
   useEffect(() => {
     ensureCardStyles();
@@ -74,7 +141,9 @@ export function RelationGraph({ data, show, projectRoot }: Props) {
           symbol: c.from.name,
           path: p,
           line: c.from.range.start.line,
+          column: c.from.range.start.character,
           inProject: isInProject(p, projectRoot),
+          calls: c.fromRanges.length,
         });
       }
     }
@@ -86,7 +155,9 @@ export function RelationGraph({ data, show, projectRoot }: Props) {
           symbol: c.to.name,
           path: p,
           line: c.to.range.start.line,
+          column: c.to.range.start.character,
           inProject: isInProject(p, projectRoot),
+          calls: c.fromRanges.length,
         });
       }
     }
@@ -98,37 +169,35 @@ export function RelationGraph({ data, show, projectRoot }: Props) {
           symbol: null,
           path: p,
           line: r.range.start.line,
+          column: r.range.start.character,
           inProject: isInProject(p, projectRoot),
+          calls: 1,
         });
       }
     }
     return arr;
   }, [data, show, projectRoot]);
 
-  // snippet 取得 + node/edge 組み立て
+  // snippet 取得 (レイアウトとは分離。向きの切替で再取得しない)
   useEffect(() => {
     if (!data) {
-      setGraph({ nodes: [], edges: [], trimmed: 0 });
+      setFetched(null);
       return;
     }
     let cancelled = false;
     const requestedTotal = targets.length + 1; // +1 = origin
-    const trimmed = Math.max(0, requestedTotal - MAX_TOTAL_CARDS);
-    const cap = Math.max(0, MAX_TOTAL_CARDS - 1); // origin 抜きの cap
+    const trimmed = Math.max(0, requestedTotal - maxCards);
+    const cap = Math.max(0, maxCards - 1); // origin 抜きの cap
     const trimmedTargets = trimmed > 0 ? targets.slice(0, cap) : targets;
     const ctx =
       trimmedTargets.length > 60 ? REDUCED_SNIPPET_CONTEXT : DEFAULT_SNIPPET_CONTEXT;
 
     (async () => {
       // origin スニペット
-      const originSnippetP = fs
-        .readSnippet(data.origin.path, data.origin.line, ctx)
-        .catch(() => null);
+      const originSnippetP = fs.readSnippetOrFailure(data.origin.path, data.origin.line, ctx);
       // 他カードのスニペットを並列に取得
       const targetSnippetsP = Promise.all(
-        trimmedTargets.map((t) =>
-          fs.readSnippet(t.path, t.line, ctx).catch(() => null),
-        ),
+        trimmedTargets.map((t) => fs.readSnippetOrFailure(t.path, t.line, ctx)),
       );
 
       const [originSnippet, snippets] = await Promise.all([
@@ -136,106 +205,215 @@ export function RelationGraph({ data, show, projectRoot }: Props) {
         targetSnippetsP,
       ]);
       if (cancelled) return;
+      setFetched({ data, targets: trimmedTargets, originSnippet, snippets, trimmed });
+    })();
 
-      const nodes: Node[] = [];
-      const edges: Edge[] = [];
-
-      const originData: RelationCardData = {
-        variant: "origin",
-        symbol: data.origin.name || "(cursor)",
-        path: data.origin.path,
-        line: data.origin.line,
-        snippet: originSnippet?.lines ?? [],
-        snippetStart: originSnippet?.start_line ?? data.origin.line,
-        inProject: true,
-        badge: "origin",
-      };
+    return () => {
+      cancelled = true;
+    };
+  }, [data, targets, maxCards]);
+
+  // node/edge 組み立て
+  const graph = useMemo(() => {
+    if (!fetched) return { nodes: [] as Node[], edges: [] as Edge[], trimmed: 0, layout };
+    const { data, targets: trimmedTargets, originSnippet, snippets, trimmed } = fetched;
+    const nodes: Node[] = [];
+    const edges: Edge[] = [];
+    const handles = handlePositions(layout);
+
+    const originData: RelationCardData = {
+      variant: "origin",
+      symbol: data.origin.name || "(cursor)",
+      path: data.origin.path,
+      line: data.origin.line,
+      ...snippetFields(originSnippet, data.origin.line),
+      inProject: true,
+      badge: "origin",
+    };
+    nodes.push({
+      id: "origin",
+      type: "card",
+      data: originData,
+      position: { x: 0, y: 0 },
+      draggable: true,
+      ...handles,
+    });
+
+    // 種別ごとに振り分けてからレイアウト
+    const buckets: Record<RelationVariant, RawTarget[]> = {
+      caller: [],
+      callee: [],
+      reference: [],
+      frame: [],
+      origin: [],
+      custom: [],
+    };
+    const snippetByIndex = snippets;
+    trimmedTargets.forEach((t) => buckets[t.variant].push(t));
+
+    const heightOf = (t: RawTarget) =>
+      estimateCardHeight(cardBodyLines(snippetByIndex[trimmedTargets.indexOf(t)]));
+    const originHeight = estimateCardHeight(cardBodyLines(originSnippet));
+    let callerPos: XY[];
+    let calleePos: XY[];
+    let refPos: XY[];
+
+    if (layout === "radial") {
+      // origin を中心に caller → callee → reference の順で 1 周に並べる
+      // (左端から時計回りなので caller が左上、callee が右側に来る)
+      const ring = [...buckets.caller, ...buckets.callee, ...buckets.reference];
+      const radius = ringRadius(ring.length, CARD_WIDTH + ROW_GAP, CARD_WIDTH + COL_GAP);
+      const center = { x: CARD_WIDTH / 2, y: originHeight / 2 };
+      const points = ringPoints(ring.length, radius, center, Math.PI).map((p, k) => ({
+        x: p.x - CARD_WIDTH / 2,
+        y: p.y - heightOf(ring[k]) / 2,
+      }));
+      const nc = buckets.caller.length;
+      const ne = buckets.callee.length;
+      callerPos = points.slice(0, nc);
+      calleePos = points.slice(nc, nc + ne);
+      refPos = points.slice(nc + ne);
+    } else {
+      // 主軸 = 層 (caller → origin → callee) が並ぶ向き、交差軸 = 同じ層内の並び。
+      // 高さはスニペット行数から推定した実寸、幅は固定 (CARD_WIDTH)。
+      const horizontal = layout === "horizontal";
+      const originCross = horizontal ? originHeight / 2 : CARD_WIDTH / 2;
+      const crossSizes = (ts: RawTarget[]) =>
+        ts.map((t) => (horizontal ? heightOf(t) : CARD_WIDTH));
+      const mainSize = (ts: RawTarget[]) =>
+        horizontal ? CARD_WIDTH : Math.max(0, ...ts.map(heightOf));
+
+      const callerSlot = -mainSize(buckets.caller) - COL_GAP;
+      const calleeSlot = (horizontal ? CARD_WIDTH : originHeight) + COL_GAP;
+      const refSlot = 0;
+      callerPos = centeredOffsets(crossSizes(buckets.caller), ROW_GAP, originCross).map(
+        (c) => place(layout, callerSlot, c),
+      );
+      calleePos = centeredOffsets(crossSizes(buckets.callee), ROW_GAP, originCross).map(
+        (c) => place(layout, calleeSlot, c),
+      );
+      refPos = stackOffsets(
+        crossSizes(buckets.reference),
+        ROW_GAP,
+        (horizontal ? originHeight : CARD_WIDTH) + REF_GAP,
+      ).map((c) => place(layout, refSlot, c));
+    }
+
+    buckets.caller.forEach((t, i) => {
+      const idx = trimmedTargets.indexOf(t);
+      const snippet = snippetByIndex[idx];
+      const id = `caller-${i}`;
       nodes.push({
-        id: "origin",
+        id,
         type: "card",
-        data: originData,
-        position: { x: 0, y: 0 },
+        data: makeCard(t, snippet),
+        position: callerPos[i],
         draggable: true,
+        ...handles,
       });
+      edges.push(
+        makeEdge(`e-${id}`, id, "origin", "#5eb2ff", callsLabel(t.calls), undefined, t.calls),
+      );
+    });
 
-      // 列ごとにレイアウト
-      const buckets: Record<RelationVariant, RawTarget[]> = {
-        caller: [],
-        callee: [],
-        reference: [],
-        frame: [],
-        origin: [],
-        custom: [],
-      };
-      const snippetByIndex = snippets;
-      trimmedTargets.forEach((t) => buckets[t.variant].push(t));
-
-      const callerSlot = -CARD_WIDTH - COL_GAP;
-      const calleeSlot = CARD_WIDTH + COL_GAP;
-      const refSlot = 0;
-
-      buckets.caller.forEach((t, i) => {
-        const idx = trimmedTargets.indexOf(t);
-        const snippet = snippetByIndex[idx];
-        const id = `caller-${i}`;
-        nodes.push({
-          id,
-          type: "card",
-          data: makeCard(t, snippet),
-          position: {
-            x: callerSlot,
-            y: -((buckets.caller.length - 1) * (CARD_HEIGHT + ROW_GAP)) / 2 +
-              i * (CARD_HEIGHT + ROW_GAP),
-          },
-          draggable: true,
-        });
-        edges.push(makeEdge(`e-${id}`, id, "origin", "#5eb2ff", "calls"));
+    buckets.callee.forEach((t, i) => {
+      const idx = trimmedTargets.indexOf(t);
+      const snippet = snippetByIndex[idx];
+      const id = `callee-${i}`;
+      nodes.push({
+        id,
+        type: "card",
+        data: makeCard(t, snippet),
+        position: calleePos[i],
+        draggable: true,
+        ...handles,
       });
+      edges.push(
+        makeEdge(`e-${id}`, "origin", id, "#7ddba2", callsLabel(t.calls), undefined, t.calls),
+      );
+    });
 
-      buckets.callee.forEach((t, i) => {
-        const idx = trimmedTargets.indexOf(t);
-        const snippet = snippetByIndex[idx];
-        const id = `callee-${i}`;
-        nodes.push({
-          id,
-          type: "card",
-          data: makeCard(t, snippet),
-          position: {
-            x: calleeSlot,
-            y: -((buckets.callee.length - 1) * (CARD_HEIGHT + ROW_GAP)) / 2 +
-              i * (CARD_HEIGHT + ROW_GAP),
-          },
-          draggable: true,
-        });
-        edges.push(makeEdge(`e-${id}`, "origin", id, "#7ddba2", "calls"));
+    buckets.reference.forEach((t, i) => {
+      const idx = trimmedTargets.indexOf(t);
+      const snippet = snippetByIndex[idx];
+      const id = `ref-${i}`;
+      nodes.push({
+        id,
+        type: "card",
+        data: makeCard(t, snippet),
+        position: refPos[i],
+        draggable: true,
+        ...handles,
       });
+      edges.push(makeEdge(`e-${id}`, "origin", id, "#efc56a", "refs", "4 2"));
+    });
 
-      buckets.reference.forEach((t, i) => {
-        const idx = trimmedTargets.indexOf(t);
-        const snippet = snippetByIndex[idx];
-        const id = `ref-${i}`;
-        nodes.push({
-          id,
-          type: "card",
-          data: makeCard(t, snippet),
-          position: {
-            x: refSlot,
-            y: CARD_HEIGHT + 80 + i * (CARD_HEIGHT + ROW_GAP),
-          },
-          draggable: true,
-        });
-        edges.push(
-          makeEdge(`e-${id}`, "origin", id, "#efc56a", undefined, "4 2"),
-        );
-      });
+    if (layout === "radial") {
+      // 放射配置ではカード中心どうしを直線で結ぶ (RelationCard 側で handle を中央へ)。
+      // 矢じりが相手カードの下に隠れないよう、線の端はカードの枠で止める
+      for (const n of nodes) n.data = { ...n.data, centerHandles: true };
+      for (const e of edges) e.type = "border";
+    }
 
-      setGraph({ nodes, edges, trimmed });
-    })();
+    return { nodes, edges, trimmed, layout };
+  }, [fetched, layout]);
 
-    return () => {
-      cancelled = true;
+  // ドラッグで動かした位置は graph を作り直す (カーソル移動 / 配置切替) まで保持する
+  const [nodes, setNodes, onNodesChange] = useNodesState<Node>([]);
+  const nodesRef = useRef<Node[]>([]);
+  nodesRef.current = nodes;
+  const flowRef = useRef<ReactFlowInstance | null>(null);
+  const prevLayoutRef = useRef(layout);
+  const [labelsHidden, setLabelsHidden] = useState(false);
+  // 配線方式とラベルの出し分けはエッジだけに掛ける。ノードを作り直すと
+  // ドラッグで動かしたカードが計算位置に戻ってしまうため
+  const edges = useMemo(() => {
+    // smoothstep = 縦横の折れ線で、角は React Flow 既定の半径で丸める (放射配置は対象外)
+    const orthogonal = routing === "orthogonal" && graph.layout !== "radial";
+    if (!orthogonal && !labelsHidden) return graph.edges;
+    return graph.edges.map((e) => ({
+      ...e,
+      ...(orthogonal ? { type: "smoothstep" } : {}),
+      ...(labelsHidden ? { label: undefined } : {}),
+    }));
+  }, [graph.edges, graph.layout, routing, labelsHidden]);
+
+  // 配置切替時は現在位置 (アニメーション途中ならその補間位置) から新しい位置へ
+  // LAYOUT_ANIM_MS かけて動かす。エッジはノード位置から毎フレーム引き直される。
+  useEffect(() => {
+    const target = graph.nodes;
+    const relayout = prevLayoutRef.current !== graph.layout;
+    prevLayoutRef.current = graph.layout;
+    const from = new Map(nodesRef.current.map((n) => [n.id, n.position]));
+    if (!relayout || from.size === 0) {
+      setNodes(target);
+      return;
+    }
+    // graph の差し替えは開始時の 1 回だけ。以後のフレームは位置だけを更新し、
+    // React Flow が付けた measured / selected などは現在のノードから引き継ぐ
+    const to = new Map(target.map((n) => [n.id, n.position]));
+    setNodes(target.map((n) => ({ ...n, position: from.get(n.id) ?? n.position })));
+    let frame = 0;
+    const start = performance.now();
+    const step = (now: number) => {
+      const t = Math.min(1, (now - start) / LAYOUT_ANIM_MS);
+      const k = easeInOutCubic(t);
+      setNodes((cur) =>
+        cur.map((n) => {
+          const a = from.get(n.id);
+          const b = to.get(n.id);
+          return a && b ? { ...n, position: lerpXY(a, b, k) } : n;
+        }),
+      );
+      if (t < 1) {
+        frame = requestAnimationFrame(step);
+      } else {
+        void flowRef.current?.fitView({ duration: FIT_ANIM_MS });
+      }
     };
-  }, [data, targets]);
+    frame = requestAnimationFrame(step);
+    return () => cancelAnimationFrame(frame);
+  }, [graph, setNodes]);
 
   if (!data) {
     return (
@@ -263,13 +441,19 @@ export function RelationGraph({ data, show, projectRoot }: Props) {
             fontFamily: "ui-monospace, Consolas, monospace",
           }}
         >
-          表示上限 100 件 ({graph.trimmed} 件は省略)
+          表示上限 {maxCards} 件 ({graph.trimmed} 件は省略、Control Panel の設定で変更可)
         </div>
       )}
       <ReactFlow
-        nodes={graph.nodes}
-        edges={graph.edges}
+        nodes={nodes}
+        onNodesChange={onNodesChange}
+        edges={edges}
         nodeTypes={nodeTypes}
+        edgeTypes={edgeTypes}
+        onInit={(instance) => {
+          flowRef.current = instance;
+        }}
+        onMove={(_, viewport) => setLabelsHidden(viewport.zoom < EDGE_LABEL_MIN_ZOOM)}
         fitView
         proOptions={{ hideAttribution: true }}
         colorMode="dark"
@@ -283,21 +467,38 @@ export function RelationGraph({ data, show, projectRoot }: Props) {
   );
 }
 
+/** 主軸 / 交差軸の座標を layout に応じて x / y へ写す。 */
+function place(layout: RelationLayout, main: number, cross: number): XY {
+  return layout === "vertical" ? { x: cross, y: main } : { x: main, y: cross };
+}
+
+/** エッジの接続点。縦向きは上下中央、それ以外は左右中央。 */
+function handlePositions(layout: RelationLayout) {
+  return layout !== "vertical"
+    ? { sourcePosition: Position.Right, targetPosition: Position.Left }
+    : { sourcePosition: Position.Bottom, targetPosition: Position.Top };
+}
+
 function makeCard(
   t: RawTarget,
-  snippet: { start_line: number; lines: string[] } | null,
+  snippet: Snippet | SnippetFailure,
 ): RelationCardData {
   return {
     variant: t.variant,
     symbol: t.symbol,
     path: t.path,
     line: t.line,
-    snippet: snippet?.lines ?? [],
-    snippetStart: snippet?.start_line ?? t.line,
+    column: t.column,
+    ...snippetFields(snippet, t.line),
     inProject: t.inProject,
   };
 }
 
+/** 同じ相手を複数箇所から呼んでいれば回数を添える */
+function callsLabel(calls: number): string {
+  return calls > 1 ? `calls ×${calls}` : "calls";
+}
+
 function makeEdge(
   id: string,
   source: string,
@@ -305,13 +506,19 @@ function makeEdge(
   color: string,
   label?: string,
   dash?: string,
+  calls = 1,
 ): Edge {
+  const strokeWidth = edgeStrokeWidth(calls);
   return {
     id,
     source,
     target,
     label,
-    style: dash ? { stroke: color, strokeDasharray: dash } : { stroke: color },
+    // 呼び出し方向 (source → target) が一目で分かるよう矢じりを付ける
+    markerEnd: { type: MarkerType.ArrowClosed, color, width: 16, height: 16 },
+    style: dash
+      ? { stroke: color, strokeWidth, strokeDasharray: dash }
+      : { stroke: color, strokeWidth },
     labelStyle: { fill: color, fontSize: 10 },
     labelBgStyle: { fill: "#11141a", fillOpacity: 0.8 },
   };
@@ -3,26 +3,44 @@
  * - frame 1 つ = `RelationCard` (variant="frame")
  * - 上から下へ #0 → 最深 frame
  * - in-project はクリック → `open_at`
- * - 100 frame を超えたら超過分を捨て、コンテキスト行も短縮
+ * - 上限 (既定 100、`iter:max_cards`) を超えたら超過分を捨て、コンテキスト行も短縮
  */
-import { useEffect, useState } from "react";
-import { ReactFlow, Background, Controls, type Node, type Edge } from "@xyflow/react";
+import { useEffect, useMemo, useState } from "react";
+import {
+  ReactFlow,
+  Background,
+  Controls,
+  useNodesState,
+  MarkerType,
+  type Node,
+  type Edge,
+} from "@xyflow/react";
 import "@xyflow/react/dist/style.css";
 import { fs, type StackFrame } from "./lsp";
-import { RelationCard, ensureCardStyles, type RelationCardData } from "./RelationCard";
+import { stackOffsets } from "./layout";
+import {
+  RelationCard,
+  cardBodyLines,
+  ensureCardStyles,
+  estimateCardHeight,
+  loadMaxCards,
+  snippetFields,
+  type RelationCardData,
+} from "./RelationCard";
 
 interface Props {
   frames: StackFrame[];
 }
 
-const MAX_FRAMES = 100;
 const DEFAULT_SNIPPET_CONTEXT = 5;
 const REDUCED_SNIPPET_CONTEXT = 2;
-const FRAME_HEIGHT = 200;
+const FRAME_GAP = 40;
 
 const nodeTypes = { card: RelationCard };
 
 export function StackTraceGraph({ frames }: Props) {
+  // Control Panel の設定を変えたら次のグラフ化から効くよう、frame ごとに読み直す
+  const maxFrames = useMemo(loadMaxCards, [frames]);
   const [graph, setGraph] = useState<{ nodes: Node[]; edges: Edge[]; trimmed: number }>({
     nodes: [],
     edges: [],
@@ -33,27 +51,37 @@ export function StackTraceGraph({ frames }: Props) {
     ensureCardStyles();
   }, []);
 
+  // ドラッグで動かした位置は graph を作り直すまで保持する
+  const [nodes, setNodes, onNodesChange] = useNodesState<Node>([]);
+  useEffect(() => {
+    setNodes(graph.nodes);
+  }, [graph.nodes, setNodes]);
+
   useEffect(() => {
     if (!frames.length) {
       setGraph({ nodes: [], edges: [], trimmed: 0 });
       return;
     }
     let cancelled = false;
-    const trimmed = Math.max(0, frames.length - MAX_FRAMES);
-    const used = trimmed > 0 ? frames.slice(0, MAX_FRAMES) : frames;
+    const trimmed = Math.max(0, frames.length - maxFrames);
+    const used = trimmed > 0 ? frames.slice(0, maxFrames) : frames;
     const ctx = used.length > 60 ? REDUCED_SNIPPET_CONTEXT : DEFAULT_SNIPPET_CONTEXT;
 
     (async () => {
       const snippets = await Promise.all(
         used.map((f) =>
           // stack frame の line は 1-based、Rust 側は 0-based 想定なので -1
-          fs.readSnippet(f.path, Math.max(0, f.line - 1), ctx).catch(() => null),
+          fs.readSnippetOrFailure(f.path, Math.max(0, f.line - 1), ctx),
         ),
       );
       if (cancelled) return;
 
       const nodes: Node[] = [];
       const edges: Edge[] = [];
+      const ys = stackOffsets(
+        snippets.map((snip) => estimateCardHeight(cardBodyLines(snip))),
+        FRAME_GAP,
+      );
 
       used.forEach((f, i) => {
         const id = `frame-${i}`;
@@ -63,8 +91,9 @@ export function StackTraceGraph({ frames }: Props) {
           symbol: f.function ?? "<anon>",
           path: f.path,
           line: Math.max(0, f.line - 1),
-          snippet: snip?.lines ?? [],
-          snippetStart: snip?.start_line ?? Math.max(0, f.line - 1),
+          // column も 1-based
+          column: f.column ? f.column - 1 : undefined,
+          ...snippetFields(snip, Math.max(0, f.line - 1)),
           inProject: f.in_project,
           badge: `#${f.index}`,
         };
@@ -72,7 +101,7 @@ export function StackTraceGraph({ frames }: Props) {
           id,
           type: "card",
           data,
-          position: { x: 0, y: i * FRAME_HEIGHT },
+          position: { x: 0, y: ys[i] },
           draggable: true,
         });
         if (i > 0) {
@@ -80,6 +109,7 @@ export function StackTraceGraph({ frames }: Props) {
             id: `e-${i}`,
             source: `frame-${i - 1}`,
             target: id,
+            markerEnd: { type: MarkerType.ArrowClosed, color: "#9a8cff", width: 16, height: 16 },
             style: { stroke: "#9a8cff" },
           });
         }
@@ -91,7 +121,7 @@ export function StackTraceGraph({ frames }: Props) {
     return () => {
       cancelled = true;
     };
-  }, [frames]);
+  }, [frames, maxFrames]);
 
   if (!frames.length) {
     return (
@@ -119,11 +149,12 @@ export function StackTraceGraph({ frames }: Props) {
             fontFamily: "ui-monospace, Consolas, monospace",
           }}
         >
-          表示上限 {MAX_FRAMES} ({graph.trimmed} frame 省略)
+          表示上限 {maxFrames} ({graph.trimmed} frame 省略、Control Panel の設定で変更可)
         </div>
       )}
       <ReactFlow
-        nodes={graph.nodes}
+        nodes={nodes}
+        onNodesChange={onNodesChange}
         edges={graph.edges}
         nodeTypes={nodeTypes}
         fitView
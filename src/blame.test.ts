@@ -0,0 +1,49 @@
+import { describe, it, expect } from "vitest";
+import { authorInitials, blameLabel, relativeAge } from "./blame";
+
+describe("relativeAge", () => {
+  it("picks the largest whole unit", () => {
+    expect(relativeAge(5)).toBe("now");
+    expect(relativeAge(5 * 60)).toBe("5m");
+    expect(relativeAge(3 * 3600 + 59)).toBe("3h");
+    expect(relativeAge(2 * 86400)).toBe("2d");
+    expect(relativeAge(120 * 86400)).toBe("4mo");
+    expect(relativeAge(400 * 86400)).toBe("1y");
+  });
+
+  it("treats clock skew into the future as now", () => {
+    expect(relativeAge(-100)).toBe("now");
+  });
+});
+
+describe("authorInitials", () => {
+  it("uses first and last words", () => {
+    expect(authorInitials("Alice Example")).toBe("AE");
+    expect(authorInitials("alice b. example")).toBe("AE");
+    expect(authorInitials("bob")).toBe("B");
+    expect(authorInitials("山田 太郎")).toBe("山太");
+    expect(authorInitials("  ")).toBe("?");
+  });
+});
+
+describe("blameLabel", () => {
+  it("abbreviates hash, author and age", () => {
+    const b = {
+      line: 0,
+      hash: "1234567890123456789012345678901234567890",
+      author: "Alice Example",
+      author_time: 1_700_000_000,
+    };
+    expect(blameLabel(b, 1_700_000_000 + 3 * 86400)).toBe("1234567 AE 3d");
+  });
+
+  it("marks uncommitted lines", () => {
+    const b = { line: 0, hash: "0".repeat(40), author: "Not Committed Yet", author_time: 0 };
+    expect(blameLabel(b, 0)).toBe("未コミット");
+  });
+
+  it("marks uncommitted lines in SHA-256 repositories", () => {
+    const b = { line: 0, hash: "0".repeat(64), author: "Not Committed Yet", author_time: 0 };
+    expect(blameLabel(b, 0)).toBe("未コミット");
+  });
+});
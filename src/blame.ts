@@ -0,0 +1,39 @@
+/**
+ * git blame 表示用の整形 (React / Monaco 非依存)。
+ *
+ * 1 行あたり `abc1234 AE 3d` 程度の短い列にし、コードの邪魔をしない幅に収める。
+ */
+
+import type { BlameLine } from "./lsp";
+
+/** `git blame` が未コミット行に付ける hash (SHA-1 で 40 桁、SHA-256 で 64 桁の 0) */
+const UNCOMMITTED = /^0+$/;
+
+/** 経過秒数を `now` / `5m` / `3h` / `2d` / `4mo` / `1y` にする。 */
+export function relativeAge(seconds: number): string {
+  const s = Math.max(0, seconds);
+  if (s < 60) return "now";
+  const m = Math.floor(s / 60);
+  if (m < 60) return `${m}m`;
+  const h = Math.floor(m / 60);
+  if (h < 24) return `${h}h`;
+  const d = Math.floor(h / 24);
+  if (d < 30) return `${d}d`;
+  if (d < 365) return `${Math.floor(d / 30)}mo`;
+  return `${Math.floor(d / 365)}y`;
+}
+
+/** "Alice Example" → "AE"、"bob" → "B"。最大 2 文字。 */
+export function authorInitials(name: string): string {
+  const words = name.trim().split(/\s+/).filter(Boolean);
+  if (words.length === 0) return "?";
+  const picked = words.length === 1 ? [words[0]] : [words[0], words[words.length - 1]];
+  return picked.map((w) => [...w][0].toUpperCase()).join("");
+}
+
+/** 1 行分の blame 列。`nowSec` は unix 秒。 */
+export function blameLabel(b: BlameLine, nowSec: number): string {
+  if (UNCOMMITTED.test(b.hash)) return "未コミット";
+  const initials = authorInitials(b.author).padEnd(2);
+  return `${b.hash.slice(0, 7)} ${initials} ${relativeAge(nowSec - b.author_time)}`;
+}
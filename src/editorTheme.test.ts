@@ -0,0 +1,23 @@
+import { describe, it, expect } from "vitest";
+import { contrastRatio, ITER_DARK_BACKGROUND, ITER_DARK_RULES } from "./editorTheme";
+
+describe("contrastRatio", () => {
+  it("matches the WCAG extremes", () => {
+    expect(contrastRatio("#ffffff", "#000000")).toBeCloseTo(21);
+    expect(contrastRatio("808080", "808080")).toBeCloseTo(1);
+  });
+});
+
+describe("iter-dark", () => {
+  it("keeps every token color readable on the editor background", () => {
+    for (const rule of ITER_DARK_RULES) {
+      if (!rule.foreground) continue;
+      // コメントは意図的に沈めるので少し緩め
+      const min = rule.token === "comment" ? 3 : 4.5;
+      expect(
+        contrastRatio(rule.foreground, ITER_DARK_BACKGROUND),
+        `${rule.token} #${rule.foreground}`,
+      ).toBeGreaterThanOrEqual(min);
+    }
+  });
+});
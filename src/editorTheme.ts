@@ -0,0 +1,88 @@
+/**
+ * File ウインドウの Monaco テーマ。
+ *
+ * 組み込みの vs-dark は背景が #1e1e1e で、アプリ本体 (#0e0f12 / #11141a 基調) と
+ * 並べると浮く。ここではアプリの配色から組んだ "iter-dark" を定義し、既定で使う。
+ * `iter:editor_theme` に Monaco 組み込みテーマ名を入れればそちらに切り替えられる。
+ */
+
+import type * as Monaco from "monaco-editor";
+
+export const EDITOR_THEME_KEY = "iter:editor_theme";
+export const ITER_DARK = "iter-dark";
+/** 選択可能なテーマ。iter-dark 以外は Monaco 組み込み */
+export const EDITOR_THEMES = [ITER_DARK, "vs-dark", "hc-black", "vs"] as const;
+
+export const ITER_DARK_BACKGROUND = "#0e0f12";
+
+/** token 色 (`#` なし、Monaco の rules 形式) */
+export const ITER_DARK_RULES: Monaco.editor.ITokenThemeRule[] = [
+  { token: "", foreground: "c9d3e0" },
+  { token: "comment", foreground: "6b7383", fontStyle: "italic" },
+  { token: "keyword", foreground: "7aa2ff" },
+  { token: "string", foreground: "8fd18f" },
+  { token: "number", foreground: "efc56a" },
+  { token: "type", foreground: "5fc9d0" },
+  { token: "identifier", foreground: "c9d3e0" },
+  { token: "delimiter", foreground: "8993a4" },
+  { token: "operator", foreground: "8993a4" },
+  { token: "keyword.directive", foreground: "c792ea" },
+  { token: "annotation", foreground: "c792ea" },
+  { token: "regexp", foreground: "e6a06a" },
+  { token: "invalid", foreground: "e66060" },
+];
+
+const ITER_DARK_THEME: Monaco.editor.IStandaloneThemeData = {
+  base: "vs-dark",
+  inherit: true,
+  rules: ITER_DARK_RULES,
+  colors: {
+    "editor.background": ITER_DARK_BACKGROUND,
+    "editor.foreground": "#c9d3e0",
+    "editorLineNumber.foreground": "#4a4f5a",
+    "editorLineNumber.activeForeground": "#8993a4",
+    "editor.lineHighlightBackground": "#14171d",
+    "editor.selectionBackground": "#2a3a5c",
+    "editorCursor.foreground": "#7aa2ff",
+    "editorGutter.background": ITER_DARK_BACKGROUND,
+    "editorStickyScroll.background": "#11141a",
+    "minimap.background": "#0b0c0f",
+    "editorBracketMatch.background": "#2a3a5c",
+    "editorBracketMatch.border": "#7aa2ff",
+    "editorBracketHighlight.foreground1": "#efc56a",
+    "editorBracketHighlight.foreground2": "#c792ea",
+    "editorBracketHighlight.foreground3": "#5fc9d0",
+    "editorBracketHighlight.unexpectedBracket.foreground": "#e66060",
+  },
+};
+
+/** Monaco に iter-dark を登録する (`<Editor beforeMount>` から呼ぶ)。 */
+export function defineIterTheme(monaco: typeof Monaco) {
+  monaco.editor.defineTheme(ITER_DARK, ITER_DARK_THEME);
+}
+
+export function loadEditorTheme(): string {
+  try {
+    const saved = localStorage.getItem(EDITOR_THEME_KEY);
+    return saved && (EDITOR_THEMES as readonly string[]).includes(saved) ? saved : ITER_DARK;
+  } catch {
+    return ITER_DARK;
+  }
+}
+
+/** WCAG の相対輝度。`rrggbb` / `#rrggbb` を受け取る。 */
+function luminance(hex: string): number {
+  const h = hex.replace(/^#/, "");
+  const [r, g, b] = [0, 2, 4].map((i) => {
+    const c = parseInt(h.slice(i, i + 2), 16) / 255;
+    return c <= 0.03928 ? c / 12.92 : Math.pow((c + 0.055) / 1.055, 2.4);
+  });
+  return 0.2126 * r + 0.7152 * g + 0.0722 * b;
+}
+
+/** WCAG のコントラスト比 (1〜21)。 */
+export function contrastRatio(fg: string, bg: string): number {
+  const a = luminance(fg);
+  const b = luminance(bg);
+  return (Math.max(a, b) + 0.05) / (Math.min(a, b) + 0.05);
+}
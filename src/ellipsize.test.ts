@@ -0,0 +1,54 @@
+import { describe, it, expect } from "vitest";
+import { ellipsizeEnd, ellipsizePath, type Measure } from "./ellipsize";
+
+/** 1 code point = 7px、Latin-1 の外 (全角・絵文字・「…」) は 14px */
+const measure: Measure = (s) =>
+  [...s].reduce((w, c) => w + (c.charCodeAt(0) > 0xff ? 14 : 7), 0);
+
+describe("ellipsizeEnd", () => {
+  it("keeps strings that already fit", () => {
+    expect(ellipsizeEnd("main", 100, measure)).toBe("main");
+    expect(ellipsizeEnd("", 0, measure)).toBe("");
+  });
+
+  it("cuts ASCII to the widest prefix that fits with the ellipsis", () => {
+    const out = ellipsizeEnd("processIncomingRequest", 70, measure);
+    expect(out).toBe("processI…");
+    expect(measure(out)).toBeLessThanOrEqual(70);
+  });
+
+  it("measures wide characters by width, not count", () => {
+    const out = ellipsizeEnd("日本語のシンボル名", 70, measure);
+    expect(out).toBe("日本語の…");
+    expect(measure(out)).toBeLessThanOrEqual(70);
+  });
+
+  it("does not split surrogate pairs", () => {
+    const out = ellipsizeEnd("a😀😀😀", 40, measure);
+    expect(out).toBe("a😀…");
+  });
+});
+
+describe("ellipsizePath", () => {
+  const path = "/home/me/projects/engine/src/render/vulkan/device.cpp";
+
+  it("returns short paths unchanged", () => {
+    expect(ellipsizePath("/src/a.cpp", 200, measure)).toBe("/src/a.cpp");
+  });
+
+  it("keeps the file name and as many trailing dirs as fit", () => {
+    const out = ellipsizePath(path, 7 * 30, measure);
+    expect(out).toBe("/…/render/vulkan/device.cpp");
+    expect(measure(out)).toBeLessThanOrEqual(7 * 30);
+  });
+
+  it("keeps the drive on Windows paths", () => {
+    const out = ellipsizePath("C:\\work\\engine\\src\\render\\device.cpp", 7 * 25, measure);
+    expect(out).toBe("C:\\…\\render\\device.cpp");
+  });
+
+  it("falls back to end-ellipsizing the file name when nothing else fits", () => {
+    const out = ellipsizePath(path, 7 * 8, measure);
+    expect(out).toBe("device…");
+  });
+});
@@ -0,0 +1,60 @@
+/**
+ * 幅 (px) に収まるよう文字列を「…」で詰める。
+ *
+ * 幅の測り方は呼び出し側が `measure` で渡す (実画面では canvas の
+ * measureText、テストでは 1 文字 = 固定幅)。文字数ではなく実寸で切るので、
+ * 全角混じりのパスでも詰めすぎ / はみ出しが起きない。
+ */
+
+export type Measure = (text: string) => number;
+
+const ELLIPSIS = "…";
+
+/** 末尾を「…」にして `maxWidth` に収める。収まるならそのまま返す。 */
+export function ellipsizeEnd(text: string, maxWidth: number, measure: Measure): string {
+  if (measure(text) <= maxWidth) return text;
+  // code point 単位で切る (サロゲートペアを割らない)
+  const chars = [...text];
+  let lo = 0;
+  let hi = chars.length;
+  // 「先頭 n 文字 + …」が収まる最大の n を二分探索
+  while (lo < hi) {
+    const mid = Math.ceil((lo + hi) / 2);
+    if (measure(chars.slice(0, mid).join("") + ELLIPSIS) <= maxWidth) lo = mid;
+    else hi = mid - 1;
+  }
+  return chars.slice(0, lo).join("") + ELLIPSIS;
+}
+
+/**
+ * パス用: ファイル名は残し、途中のディレクトリを「…」に畳む
+ * (`/long/…/dir/file.cpp`)。ファイル名だけでも収まらなければ末尾を詰める。
+ */
+export function ellipsizePath(path: string, maxWidth: number, measure: Measure): string {
+  if (measure(path) <= maxWidth) return path;
+  const sep = path.includes("\\") && !path.includes("/") ? "\\" : "/";
+  const parts = path.split(sep);
+  const file = parts.pop() ?? path;
+  if (parts.length === 0 || measure(ELLIPSIS + sep + file) > maxWidth) {
+    return ellipsizeEnd(file, maxWidth, measure);
+  }
+  // 末尾側のディレクトリから 1 つずつ足し、収まらなくなった所で先頭と「…」で挟む
+  const head = parts[0] === "" ? sep : parts[0] + sep;
+  let tail = sep + file;
+  for (let i = parts.length - 1; i >= 1; i--) {
+    const next = sep + parts[i] + tail;
+    if (measure(head + ELLIPSIS + next) > maxWidth) break;
+    tail = next;
+  }
+  const withHead = head + ELLIPSIS + tail;
+  return measure(withHead) <= maxWidth ? withHead : ELLIPSIS + tail;
+}
+
+/** `el` のフォントで測る Measure (canvas の measureText)。 */
+export function domMeasure(el: Element): Measure {
+  const ctx = document.createElement("canvas").getContext("2d");
+  if (!ctx) return (s) => s.length * 7;
+  const cs = getComputedStyle(el);
+  ctx.font = `${cs.fontWeight} ${cs.fontSize} ${cs.fontFamily}`;
+  return (s) => ctx.measureText(s).width;
+}
@@ -3,12 +3,25 @@ import ReactDOM from "react-dom/client";
 import { FileWindow } from "./FileWindow";
 import "./styles.css";
 
-// File ウインドウは ?path=... をクエリで受け取る。
+// File ウインドウは ?path=...&line=...&col=...&follow=1 をクエリで受け取る
+// (`window.rs` の open_internal が組み立てる。line / col は 0-based)。
 const params = new URLSearchParams(window.location.search);
 const path = params.get("path") ?? "";
 
+function numParam(name: string): number | undefined {
+  const raw = params.get(name);
+  if (raw === null) return undefined;
+  const n = Number(raw);
+  return Number.isFinite(n) ? n : undefined;
+}
+
 ReactDOM.createRoot(document.getElementById("root")!).render(
   <React.StrictMode>
-    <FileWindow path={path} />
+    <FileWindow
+      path={path}
+      initialLine={numParam("line")}
+      initialCol={numParam("col")}
+      followDefinition={params.get("follow") === "1"}
+    />
   </React.StrictMode>,
 );
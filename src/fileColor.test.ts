@@ -0,0 +1,21 @@
+import { describe, it, expect } from "vitest";
+import { FILE_PALETTE, fileColor } from "./fileColor";
+
+describe("fileColor", () => {
+  it("is stable for the same file regardless of separators and case", () => {
+    const a = fileColor("C:\\Proj\\src\\main.cpp");
+    expect(fileColor("c:/proj/src/main.cpp")).toBe(a);
+    expect(fileColor("C:\\Proj\\src\\main.cpp")).toBe(a);
+  });
+
+  it("always picks from the palette and spreads files across it", () => {
+    const colors = new Set<string>();
+    for (let i = 0; i < 64; i++) {
+      const c = fileColor(`/proj/src/file${i}.cpp`);
+      expect(FILE_PALETTE).toContain(c);
+      colors.add(c);
+    }
+    // 64 ファイルで半分も使わないなら偏りすぎ
+    expect(colors.size).toBeGreaterThan(FILE_PALETTE.length / 2);
+  });
+});
@@ -0,0 +1,38 @@
+/**
+ * グラフのカードに付けるファイルごとの色 (React 非依存)。
+ *
+ * 同じファイルのカードが同じ色になるよう、パスのハッシュで固定パレットから
+ * 選ぶ。乱数や出現順を使わないので、実行やグラフの作り直しをまたいでも同じ色。
+ */
+
+/** variant の色 (RelationCard の ACCENT_BY_VARIANT) と紛れにくい色相を並べる */
+export const FILE_PALETTE = [
+  "#e06c75",
+  "#d19a66",
+  "#c3b15a",
+  "#98c379",
+  "#56b6c2",
+  "#61afef",
+  "#c678dd",
+  "#e5a0c6",
+] as const;
+
+/** 比較用に正規化したパス (区切りを `/`、大文字小文字を無視) */
+function normalize(path: string): string {
+  return path.replace(/\\/g, "/").toLowerCase();
+}
+
+/** FNV-1a (32bit)。 */
+function fnv1a32(s: string): number {
+  let h = 0x811c9dc5;
+  for (let i = 0; i < s.length; i++) {
+    h ^= s.charCodeAt(i);
+    h = Math.imul(h, 0x01000193) >>> 0;
+  }
+  return h;
+}
+
+/** `path` のファイルに割り当てる色。 */
+export function fileColor(path: string): string {
+  return FILE_PALETTE[fnv1a32(normalize(path)) % FILE_PALETTE.length];
+}
@@ -0,0 +1,19 @@
+import { useEffect } from "react";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+
+/**
+ * F11: 自ウインドウの全画面切替。
+ * 解除時の大きさ・位置は OS 側が元に戻すので、ここでは覚えておかない。
+ */
+export function useFullscreenShortcut() {
+  useEffect(() => {
+    const onKey = async (e: KeyboardEvent) => {
+      if (e.key !== "F11" || e.ctrlKey || e.altKey || e.metaKey || e.shiftKey) return;
+      e.preventDefault();
+      const w = getCurrentWebviewWindow();
+      await w.setFullscreen(!(await w.isFullscreen()));
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, []);
+}
@@ -0,0 +1,140 @@
+import { describe, it, expect } from "vitest";
+import {
+  EDGE_MAX_WIDTH,
+  centeredOffsets,
+  clipToRect,
+  easeInOutCubic,
+  edgeStrokeWidth,
+  lerpXY,
+  ringPoints,
+  ringRadius,
+  stackOffsets,
+} from "./layout";
+
+/** 隣り合う要素どうしの間隔がすべて gap 以上か */
+function noOverlap(offsets: number[], sizes: number[], gap: number): boolean {
+  for (let i = 1; i < offsets.length; i++) {
+    if (offsets[i] - (offsets[i - 1] + sizes[i - 1]) < gap - 1e-9) return false;
+  }
+  return true;
+}
+
+describe("stackOffsets", () => {
+  it("places mixed-size items without overlap", () => {
+    const sizes = [155, 40, 113, 155, 33];
+    const offsets = stackOffsets(sizes, 30);
+    expect(offsets).toEqual([0, 185, 255, 398, 583]);
+    expect(noOverlap(offsets, sizes, 30)).toBe(true);
+  });
+
+  it("honours the start offset", () => {
+    expect(stackOffsets([10, 10], 5, 100)).toEqual([100, 115]);
+  });
+
+  it("returns an empty list for no items", () => {
+    expect(stackOffsets([], 30)).toEqual([]);
+  });
+});
+
+describe("centeredOffsets", () => {
+  it("centers the whole column on the given point", () => {
+    const sizes = [100, 50, 150];
+    const offsets = centeredOffsets(sizes, 20, 0);
+    const top = offsets[0];
+    const bottom = offsets[2] + sizes[2];
+    expect(top + bottom).toBeCloseTo(0);
+    expect(noOverlap(offsets, sizes, 20)).toBe(true);
+  });
+
+  it("puts a single item centered on the point", () => {
+    expect(centeredOffsets([80], 30, 40)).toEqual([0]);
+  });
+});
+
+describe("ringPoints / ringRadius", () => {
+  it("keeps every point on the circle", () => {
+    const center = { x: 170, y: 80 };
+    for (const p of ringPoints(7, 500, center)) {
+      expect(Math.hypot(p.x - center.x, p.y - center.y)).toBeCloseTo(500);
+    }
+  });
+
+  it("spaces neighbours at least span apart at the computed radius", () => {
+    const span = 370;
+    for (const n of [2, 3, 12, 99]) {
+      const r = ringRadius(n, span, 420);
+      const pts = ringPoints(n, r, { x: 0, y: 0 });
+      for (let i = 0; i < n; i++) {
+        const a = pts[i];
+        const b = pts[(i + 1) % n];
+        expect(Math.hypot(a.x - b.x, a.y - b.y)).toBeGreaterThanOrEqual(span - 1e-6);
+      }
+    }
+  });
+
+  it("falls back to the minimum radius for sparse rings", () => {
+    expect(ringRadius(0, 370, 420)).toBe(420);
+    expect(ringRadius(1, 370, 420)).toBe(420);
+    expect(ringRadius(3, 370, 420)).toBe(420);
+  });
+
+  it("starts at the requested angle", () => {
+    const [first] = ringPoints(4, 10, { x: 0, y: 0 }, Math.PI);
+    expect(first.x).toBeCloseTo(-10);
+    expect(first.y).toBeCloseTo(0);
+  });
+});
+
+describe("easeInOutCubic / lerpXY", () => {
+  it("maps the endpoints exactly and is monotonic", () => {
+    expect(easeInOutCubic(0)).toBe(0);
+    expect(easeInOutCubic(1)).toBe(1);
+    expect(easeInOutCubic(0.5)).toBeCloseTo(0.5);
+    let prev = 0;
+    for (let i = 1; i <= 100; i++) {
+      const v = easeInOutCubic(i / 100);
+      expect(v).toBeGreaterThanOrEqual(prev);
+      prev = v;
+    }
+  });
+
+  it("interpolates between two points", () => {
+    const a = { x: 0, y: 100 };
+    const b = { x: 200, y: -100 };
+    expect(lerpXY(a, b, 0)).toEqual(a);
+    expect(lerpXY(a, b, 1)).toEqual(b);
+    expect(lerpXY(a, b, 0.25)).toEqual({ x: 50, y: 50 });
+  });
+});
+
+describe("edgeStrokeWidth", () => {
+  it("grows sub-linearly with the call count and is capped", () => {
+    expect(edgeStrokeWidth(1)).toBe(1);
+    expect(edgeStrokeWidth(2)).toBe(2);
+    expect(edgeStrokeWidth(5)).toBe(3);
+    expect(edgeStrokeWidth(3)).toBeGreaterThan(edgeStrokeWidth(2));
+    expect(edgeStrokeWidth(1000)).toBe(EDGE_MAX_WIDTH);
+    // 0 件 (fromRanges が空) でも 1px
+    expect(edgeStrokeWidth(0)).toBe(1);
+  });
+});
+
+describe("clipToRect", () => {
+  const center = { x: 100, y: 100 };
+
+  it("stops on the side the line enters from", () => {
+    // 真左から → 左辺
+    expect(clipToRect({ x: 0, y: 100 }, center, 50, 20)).toEqual({ x: 50, y: 100 });
+    // 真上から → 上辺
+    expect(clipToRect({ x: 100, y: 0 }, center, 50, 20)).toEqual({ x: 100, y: 80 });
+    // 斜め (傾きが浅い) → 左右の辺
+    expect(clipToRect({ x: 0, y: 80 }, center, 50, 20)).toEqual({ x: 50, y: 90 });
+    // 斜め (傾きが急) → 上下の辺
+    expect(clipToRect({ x: 80, y: 0 }, center, 50, 20)).toEqual({ x: 96, y: 80 });
+  });
+
+  it("returns the start when it is inside the box or at the centre", () => {
+    expect(clipToRect({ x: 110, y: 105 }, center, 50, 20)).toEqual({ x: 110, y: 105 });
+    expect(clipToRect(center, center, 50, 20)).toEqual(center);
+  });
+});
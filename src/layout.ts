@@ -0,0 +1,95 @@
+/**
+ * グラフ配置の純粋な計算部分 (React / React Flow 非依存)。
+ *
+ * カードの高さはスニペット行数で変わるので、固定ピッチで並べると短いカードの
+ * 間が空きすぎ、長いカードは隣と重なる。ここでは各カードの実寸 (推定値) を
+ * 受け取り、重ならない並び位置を返す。
+ */
+
+export interface XY {
+  x: number;
+  y: number;
+}
+
+/** `sizes` を `gap` 空けで 1 列に並べたときの各要素の先頭座標。 */
+export function stackOffsets(sizes: number[], gap: number, start = 0): number[] {
+  const out: number[] = [];
+  let cursor = start;
+  for (const size of sizes) {
+    out.push(cursor);
+    cursor += size + gap;
+  }
+  return out;
+}
+
+/** 列全体の中心が `center` に来るように `stackOffsets` する。 */
+export function centeredOffsets(sizes: number[], gap: number, center: number): number[] {
+  const total = sizes.reduce((a, b) => a + b, 0) + gap * Math.max(0, sizes.length - 1);
+  return stackOffsets(sizes, gap, center - total / 2);
+}
+
+/**
+ * 幅 `span` の要素 `count` 個を円周上に等間隔で並べても隣どうしが重ならない
+ * 最小の半径。`minRadius` 未満にはしない。
+ */
+export function ringRadius(count: number, span: number, minRadius: number): number {
+  if (count < 2) return minRadius;
+  // 隣接 2 点の弦の長さ 2r·sin(π/n) が span 以上になる r
+  return Math.max(minRadius, span / (2 * Math.sin(Math.PI / count)));
+}
+
+/**
+ * `center` を中心とする半径 `radius` の円周上に `count` 点を等間隔で置く。
+ * 1 点目は `startAngle` (ラジアン、0 = 右、画面座標なので正方向が時計回り)。
+ */
+export function ringPoints(
+  count: number,
+  radius: number,
+  center: XY,
+  startAngle = -Math.PI / 2,
+): XY[] {
+  const out: XY[] = [];
+  for (let i = 0; i < count; i++) {
+    const a = startAngle + (2 * Math.PI * i) / count;
+    out.push({ x: center.x + radius * Math.cos(a), y: center.y + radius * Math.sin(a) });
+  }
+  return out;
+}
+
+/**
+ * `center` を中心とする幅 2·halfW、高さ 2·halfH の矩形の枠と、`from` → `center`
+ * の線分が交わる点。中心どうしを結ぶ直線の端をカードの枠で止めるのに使う
+ * (中心まで引くと矢じりがカードの下に隠れる)。`from` が矩形の内側なら `from`。
+ */
+export function clipToRect(from: XY, center: XY, halfW: number, halfH: number): XY {
+  const dx = from.x - center.x;
+  const dy = from.y - center.y;
+  if (dx === 0 && dy === 0) return center;
+  const t = Math.min(
+    dx === 0 ? Infinity : halfW / Math.abs(dx),
+    dy === 0 ? Infinity : halfH / Math.abs(dy),
+  );
+  if (t >= 1) return from;
+  return { x: center.x + dx * t, y: center.y + dy * t };
+}
+
+/** ease-in-out (cubic)。t ∈ [0, 1] → [0, 1]。 */
+export function easeInOutCubic(t: number): number {
+  return t < 0.5 ? 4 * t * t * t : 1 - Math.pow(-2 * t + 2, 3) / 2;
+}
+
+/** a → b を k (0..1) で線形補間。 */
+export function lerpXY(a: XY, b: XY, k: number): XY {
+  return { x: a.x + (b.x - a.x) * k, y: a.y + (b.y - a.y) * k };
+}
+
+/** 辺の太さの上限 (px) */
+export const EDGE_MAX_WIDTH = 4;
+
+/**
+ * 呼び出し回数 `count` の辺の太さ。1 回 = 1px から平方根で伸ばし、
+ * `EDGE_MAX_WIDTH` で頭打ちにする (数十回の呼び出しで他の辺を潰さないため)。
+ */
+export function edgeStrokeWidth(count: number): number {
+  return Math.min(EDGE_MAX_WIDTH, 1 + Math.sqrt(Math.max(0, count - 1)));
+}
@@ -4,7 +4,8 @@ vi.mock("@tauri-apps/api/core", () => ({
   invoke: vi.fn(),
 }));
 
-import { uriToPath } from "./lsp";
+import { invoke } from "@tauri-apps/api/core";
+import { describeSnippetError, fs, uriToPath } from "./lsp";
 
 describe("uriToPath", () => {
   it("strips file:// from POSIX paths", () => {
@@ -29,3 +30,33 @@ describe("uriToPath", () => {
     expect(uriToPath("https://example.com/x")).toBe("https://example.com/x");
   });
 });
+
+describe("describeSnippetError", () => {
+  it("maps each SnippetError variant to a card message", () => {
+    expect(describeSnippetError({ NotFound: "/p/gone.cpp" })).toBe(
+      "ファイルが見つかりません: /p/gone.cpp — ビルド後に移動・削除された可能性があります",
+    );
+    expect(describeSnippetError({ PermissionDenied: "/p/secret.cpp" })).toBe(
+      "読み取り権限がありません: /p/secret.cpp",
+    );
+    expect(
+      describeSnippetError({ TooLarge: { path: "/p/gen.cpp", size: 48 * 1024 * 1024 } }),
+    ).toBe("ファイルが大きすぎます (48 MB): /p/gen.cpp");
+    expect(describeSnippetError({ Io: "Is a directory" })).toBe(
+      "読み込みに失敗: Is a directory",
+    );
+  });
+
+  it("falls back to the raw value for unknown errors", () => {
+    expect(describeSnippetError("boom")).toBe("boom");
+    expect(describeSnippetError({ Other: 1 })).toBe('{"Other":1}');
+  });
+});
+
+describe("fs.readSnippetOrFailure", () => {
+  it("turns a rejected read into a SnippetFailure instead of throwing", async () => {
+    vi.mocked(invoke).mockRejectedValueOnce({ NotFound: "/p/gone.cpp" });
+    const r = await fs.readSnippetOrFailure("/p/gone.cpp", 3, 5);
+    expect(r).toEqual({ error: describeSnippetError({ NotFound: "/p/gone.cpp" }) });
+  });
+});
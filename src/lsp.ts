@@ -109,13 +109,115 @@ export const win = {
 export interface Snippet {
   start_line: number;
   target_line: number;
+  total_lines: number;
   lines: string[];
 }
 
+/** `read_snippet` の失敗理由 (Rust の SnippetError を serde の既定形式で受ける) */
+export type SnippetError =
+  | { NotFound: string }
+  | { PermissionDenied: string }
+  | { TooLarge: { path: string; size: number } }
+  | { Io: string };
+
+/** カード本文の代わりに出す 1〜2 行の説明 */
+export function describeSnippetError(e: unknown): string {
+  if (typeof e !== "object" || e === null) return String(e);
+  const err = e as Partial<Record<string, unknown>>;
+  if ("NotFound" in err) {
+    return `ファイルが見つかりません: ${err.NotFound} — ビルド後に移動・削除された可能性があります`;
+  }
+  if ("PermissionDenied" in err) return `読み取り権限がありません: ${err.PermissionDenied}`;
+  if ("TooLarge" in err) {
+    const { path, size } = err.TooLarge as { path: string; size: number };
+    return `ファイルが大きすぎます (${Math.round(size / 1024 / 1024)} MB): ${path}`;
+  }
+  if ("Io" in err) return `読み込みに失敗: ${err.Io}`;
+  return JSON.stringify(e);
+}
+
+/** これを超えるファイルは対象行の周辺だけ読む (MB)。Control Panel の設定で変更可 */
+export const MAX_FILE_MB_KEY = "iter:max_file_mb";
+const DEFAULT_MAX_FILE_MB = 16;
+
+export function loadMaxFileMb(): number {
+  try {
+    const mb = Number(localStorage.getItem(MAX_FILE_MB_KEY));
+    return mb > 0 ? mb : DEFAULT_MAX_FILE_MB;
+  } catch {
+    return DEFAULT_MAX_FILE_MB;
+  }
+}
+
+/** File ウインドウの本文 (Rust の `read_file_text`) */
+export interface FileText {
+  text: string;
+  /** UTF-8 として不正なバイトを U+FFFD に置換した */
+  lossy: boolean;
+  /** 上限を超えたので対象行の周辺だけ読んだ */
+  partial: boolean;
+  /** 周辺の窓も上限に収まらず、途中の行で切った */
+  truncated: boolean;
+  /** text の 1 行目がファイルの何行目か (0-based) */
+  start_line: number;
+}
+
+/** スニペットを読めなかったカード用 */
+export interface SnippetFailure {
+  error: string;
+}
+
 export const fs = {
   async readSnippet(path: string, line: number, context: number): Promise<Snippet> {
     return invoke<Snippet>("read_snippet", { path, line, context });
   },
+  /** 失敗時は SnippetError で reject。`maxBytes` を超えると `line` 周辺だけ読む */
+  async readFileText(path: string, line?: number, maxBytes?: number): Promise<FileText> {
+    return invoke<FileText>("read_file_text", {
+      path,
+      line: line ?? null,
+      maxBytes: maxBytes ?? null,
+    });
+  },
+  /** 失敗しても reject せず、理由を SnippetFailure で返す */
+  async readSnippetOrFailure(
+    path: string,
+    line: number,
+    context: number,
+  ): Promise<Snippet | SnippetFailure> {
+    try {
+      return await fs.readSnippet(path, line, context);
+    } catch (e) {
+      return { error: describeSnippetError(e) };
+    }
+  },
+};
+
+export interface LineChange {
+  kind: "added" | "modified" | "removed";
+  /** 0-based */
+  start_line: number;
+  line_count: number;
+}
+
+export interface BlameLine {
+  /** 0-based */
+  line: number;
+  hash: string;
+  author: string;
+  /** unix 秒 */
+  author_time: number;
+}
+
+export const git = {
+  /** HEAD からの変更行。git が無い / リポジトリ外などは reject される */
+  async lineChanges(path: string): Promise<LineChange[]> {
+    return invoke<LineChange[]>("git_line_changes", { path });
+  },
+  /** `[startLine, endLine)` (0-based) の blame */
+  async blame(path: string, startLine: number, endLine: number): Promise<BlameLine[]> {
+    return invoke<BlameLine[]>("git_blame", { path, startLine, endLine });
+  },
 };
 
 /** uri から OS パスへの変換 (`file:///c:/foo` → `c:/foo`)。Windows / Unix 両対応。 */